@@ -0,0 +1,82 @@
+use std::time::{Duration, SystemTime};
+
+use crate::net::cache::{self, HttpCache};
+use crate::sections::SectionResult;
+
+use super::{print_commands, usage_error, Opts};
+
+const COMMANDS: &[(&str, &str)] = &[
+    ("ls [--dir <dir>]", "list the cached responses : status , size , age , fresh or stale , method and URL"),
+    ("clear [--dir <dir>]", "remove every cached response"),
+];
+
+/*
+    rapp1 cache <command> [args...]
+
+    the HTTP cache of the commands that use one (rapp1 joke --cache) ,
+    in $RAPP1_CACHE_DIR , or rapp1/http in the user's cache directory (see net::cache)
+*/
+pub fn run(args: &[String]) -> SectionResult {
+    match args.first().map(String::as_str) {
+        Some("ls") => ls(&args[1..]),
+        Some("clear") => clear(&args[1..]),
+        Some("help") | Some("-h") | Some("--help") => {
+            print_commands("cache", COMMANDS);
+            Ok(())
+        }
+        Some(other) => usage_error(format!("unknown command '{}' , see 'rapp1 cache help'", other)),
+        None => usage_error("missing command , see 'rapp1 cache help'"),
+    }
+}
+
+fn open(args: &[String], usage: &str) -> Result<HttpCache, Box<dyn std::error::Error>> {
+    let opts = Opts::parse(args, &[("dir", true)])?;
+    if !opts.positional.is_empty() {
+        return usage_error(usage);
+    }
+    Ok(HttpCache::new(opts.value("dir").map(Into::into).unwrap_or_else(cache::default_dir)))
+}
+
+// rapp1 cache ls [--dir <dir>]
+fn ls(args: &[String]) -> SectionResult {
+    let cache = open(args, "usage: rapp1 cache ls [--dir <dir>]")?;
+    let entries = cache.entries()?;
+    let now = SystemTime::now();
+    for e in &entries {
+        let state = if e.is_fresh(now) { "fresh" } else { "stale" };
+        println!("{:>3}  {:>9}  {:>8}  {:<5}  {:<4} {}", e.status, size(e.size), age(e.age(now)), state, e.method, e.url);
+        for (name, value) in &e.vary {
+            println!("{:>36}  vary {}: {}", "", name, value);
+        }
+    }
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    println!("{} response(s) , {} in {}", entries.len(), size(total), cache.dir().display());
+    Ok(())
+}
+
+// rapp1 cache clear [--dir <dir>]
+fn clear(args: &[String]) -> SectionResult {
+    let cache = open(args, "usage: rapp1 cache clear [--dir <dir>]")?;
+    let removed = cache.clear()?;
+    println!("removed {} response(s) from {}", removed, cache.dir().display());
+    Ok(())
+}
+
+// 532 B , 12.4 KB , 3.1 MB
+fn size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+// 45s , 12m , 3h , 2d
+fn age(age: Duration) -> String {
+    match age.as_secs() {
+        s @ 0..=59 => format!("{}s", s),
+        s @ 60..=3599 => format!("{}m", s / 60),
+        s @ 3600..=86_399 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86_400),
+    }
+}
@@ -0,0 +1,585 @@
+use std::fs::File;
+
+use serde_json::Value;
+
+use crate::customers::address::{self, GroupBy};
+use crate::customers::csv;
+use crate::customers::dedup::{self, Dedup, Survivorship};
+use crate::customers::diff;
+use crate::customers::generate::Generator;
+use crate::customers::jsonschema::{self, Schema};
+use crate::customers::mask::{Field, Masker, Strategy};
+use crate::customers::phone;
+use crate::customers::query::{self, Expr, Query};
+#[cfg(feature = "sqlite")]
+use crate::customers::sqlite::SqliteStore;
+use crate::customers::stats::Stats;
+use crate::customers::store::{self, Format};
+use crate::customers::stream::{self, CustomerReader, CustomerWriter, StreamError, Summary};
+use crate::customers::Validator;
+use crate::model::{is_country_code, Customer, NANP};
+#[cfg(feature = "sqlite")]
+use crate::model::ObjectId;
+use crate::sections::SectionResult;
+
+use super::{print_commands, usage_error, Opts};
+
+const COMMANDS: &[(&str, &str)] = &[
+    ("validate <file> [--country <code>]", "check every record of a JSON / NDJSON / CSV customer file"),
+    ("convert <input> <output> [--normalize-phones] [--country <code>] [--skip-bad]", "convert between JSON, NDJSON and CSV (by file extension)"),
+    ("count <file> [--skip-bad]", "count the customers of a JSON / NDJSON file , one record at a time"),
+    ("filter <input> <output> [field=value ...] [--where <query>] [--skip-bad]", "copy the customers matching every field=value (and the query) , one record at a time"),
+    ("stats <file> [--format text|json] [--top <n>] [--bucket <years>] [--skip-bad]", "age distribution , counts per gender / eyecolor / company , top companies , email domains"),
+    ("query <file> [<query>] [--sort <keys>] [--page <n> --size <n>] [--offset <n>] [--limit <n>] [--format json|ndjson|csv]", "filter , sort and paginate customers , e.g. 'age > 30 and company = ENOMEN' --sort -age"),
+    ("addresses <file> [city|region] [name]", "list bad addresses , count customers per city / region , or list one city / region"),
+    ("phones <file> [--country <code>]", "list phone numbers that do not parse , and customers sharing a number"),
+    ("dedup <input> [<output>] [--threshold <0..1>] [--keep first|last|most-complete] [--country <code>]", "list clusters of likely duplicates , and write the merged customers to <output>"),
+    ("diff <old> <new> [--format text|patch]", "added , removed and changed customers (by customerid) , as text or a JSON patch"),
+    ("mask <input> <output> [--name <s>] [--email <s>] [--phone <s>] [--address <s>] [--keep-extra <key,key..>] [--country <code>] [--skip-bad]", "write a copy with masked personal fields , s = keep|redact|partial|hash|fake (hash and fake use the key in $RAPP1_MASK_KEY) ; other keys of the records are redacted , but the --keep-extra ones"),
+    ("generate <output> [--count <n>] [--seed <n>] [--country <code>]", "write <n> made up customers (default 100) as JSON, NDJSON or CSV (by file extension) , the same seed gives the same customers ; phones are in the country <code> (default 1)"),
+    ("schema [--file]", "print the JSON Schema of a customer record (or of a JSON array file of customers with --file)"),
+    ("check <file> [--schema <schema.json>]", "check a JSON / NDJSON document against a JSON Schema (the customer schema by default) , every violation with its JSON pointer"),
+    #[cfg(feature = "sqlite")]
+    ("import <db> <file>", "add the customers of a JSON / NDJSON / CSV file to a SQLite database , all of them or none"),
+    #[cfg(feature = "sqlite")]
+    ("export <db> <file>", "write every customer of a SQLite database as JSON, NDJSON or CSV (by file extension)"),
+    #[cfg(feature = "sqlite")]
+    ("lookup <db> (--id <customerid> | --email <email> | --company <name>)", "the customers of a SQLite database with that customerid , email or company"),
+];
+
+// rapp1 customers <command> [args...]
+pub fn run(args: &[String]) -> SectionResult {
+    match args.first().map(String::as_str) {
+        Some("validate") => validate(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        Some("count") => count(&args[1..]),
+        Some("filter") => filter(&args[1..]),
+        Some("query") => query(&args[1..]),
+        Some("stats") => stats(&args[1..]),
+        Some("addresses") => addresses(&args[1..]),
+        Some("phones") => phones(&args[1..]),
+        Some("dedup") => dedup(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("mask") => mask(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("schema") => schema(&args[1..]),
+        Some("check") => check(&args[1..]),
+        #[cfg(feature = "sqlite")]
+        Some("import") => import(&args[1..]),
+        #[cfg(feature = "sqlite")]
+        Some("export") => export(&args[1..]),
+        #[cfg(feature = "sqlite")]
+        Some("lookup") => lookup(&args[1..]),
+        #[cfg(not(feature = "sqlite"))]
+        Some("import") | Some("export") | Some("lookup") => usage_error("rapp1 was built without SQLite , build it with --features sqlite"),
+        Some("help") | Some("-h") | Some("--help") => {
+            print_commands("customers", COMMANDS);
+            Ok(())
+        }
+        Some(other) => usage_error(format!("unknown command '{}' , see 'rapp1 customers help'", other)),
+        None => usage_error("missing command , see 'rapp1 customers help'"),
+    }
+}
+
+// --country <code> : the country of phone numbers written without a country code
+const COUNTRY: (&str, bool) = ("country", true);
+
+fn validate(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[COUNTRY])?;
+    let path = match &opts.positional[..] {
+        [path] => path,
+        _ => return usage_error("usage: rapp1 customers validate <file> [--country <code>]"),
+    };
+
+    let (customers, _) = store::read_file(path)?;
+    let mut validator = Validator::new();
+    if let Some(cc) = opts.parsed("country")? {
+        validator = validator.with_default_country(cc);
+    }
+    let reports = validator.validate_all(&customers);
+
+    let mut invalid = 0;
+    for report in &reports {
+        if report.is_valid() {
+            println!("record {} ({}) : ok", report.index + 1, report.customerid);
+            continue;
+        }
+        invalid += 1;
+        println!("record {} ({}) : {} error(s)", report.index + 1, report.customerid, report.errors.len());
+        for e in &report.errors {
+            println!("    {}", e);
+        }
+    }
+
+    println!("\n{} record(s) , {} valid , {} invalid", reports.len(), reports.len() - invalid, invalid);
+
+    if invalid > 0 {
+        return Err(format!("{} of {} record(s) are invalid", invalid, reports.len()).into());
+    }
+    Ok(())
+}
+
+/*
+    JSON and NDJSON input is streamed one record at a time (see customers::stream),
+    CSV input is loaded as a whole
+*/
+fn convert(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[COUNTRY, SKIP_BAD, ("normalize-phones", false)])?;
+    let (input, output) = match &opts.positional[..] {
+        [input, output] => (input, output),
+        _ => return usage_error("usage: rapp1 customers convert <input> <output> [--normalize-phones] [--country <code>] [--skip-bad]"),
+    };
+    let normalize_phones = opts.flag("normalize-phones");
+    let country = opts.parsed("country")?;
+
+    let mut writer = CustomerWriter::create(output)?;
+    let summary = for_each_customer(input, opts.flag("skip-bad"), |mut c| {
+        if normalize_phones {
+            if let Err(e) = phone::normalize(&mut c, country) {
+                eprintln!("warning: {} : phone '{}' kept as is : {}", c.customerid, c.phone, e);
+            }
+        }
+        writer.write(&c)
+    })?;
+    writer.commit()?;
+
+    println!("converted {} customer(s) to {:?} , {} bad record(s) skipped", summary.read, Format::from_path(output), summary.bad);
+    Ok(())
+}
+
+// --skip-bad : report records that are not valid customers , and carry on
+const SKIP_BAD: (&str, bool) = ("skip-bad", false);
+
+fn warn_bad(e: &StreamError) {
+    eprintln!("warning: skipped {}", e);
+}
+
+// call f for every customer of a file , streamed for JSON / NDJSON , loaded as a whole for CSV
+fn for_each_customer<F>(path: &str, skip_bad: bool, mut f: F) -> Result<Summary, StreamError>
+where
+    F: FnMut(Customer) -> Result<(), StreamError>,
+{
+    if Format::from_path(path) == Format::Csv {
+        let customers = csv::read_customers(File::open(path)?)?;
+        let read = customers.len();
+        for c in customers {
+            f(c)?;
+        }
+        return Ok(Summary { read, written: 0, bad: 0 });
+    }
+    stream::for_each(CustomerReader::open(path)?, skip_bad, warn_bad, f)
+}
+
+fn count(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[SKIP_BAD])?;
+    let path = match &opts.positional[..] {
+        [path] => path,
+        _ => return usage_error("usage: rapp1 customers count <file> [--skip-bad]"),
+    };
+
+    let summary = stream::count(CustomerReader::open(path)?, opts.flag("skip-bad"), warn_bad)?;
+    println!("{}", summary.read);
+    if summary.bad > 0 {
+        eprintln!("{} bad record(s) skipped", summary.bad);
+    }
+    Ok(())
+}
+
+// rapp1 customers filter in.json out.ndjson company=ENOMEN gender=female
+fn filter(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[SKIP_BAD, ("where", true)])?;
+    let (input, output, conditions) = match &opts.positional[..] {
+        [input, output, conditions @ ..] => (input, output, conditions),
+        _ => return usage_error("usage: rapp1 customers filter <input> <output> [field=value ...] [--where <query>] [--skip-bad]"),
+    };
+    let query: Option<Expr> = opts.value("where").map(str::parse).transpose()?;
+
+    let mut wanted = Vec::new();
+    for condition in conditions {
+        match condition.split_once('=') {
+            Some((field, value)) if Customer::FIELDS.contains(&field) => wanted.push((field, value)),
+            _ => return usage_error(format!("'{}' is not field=value , fields are : {}", condition, Customer::FIELDS.join(", "))),
+        }
+    }
+
+    let mut writer = CustomerWriter::create(output)?;
+    let summary = stream::filter(CustomerReader::open(input)?, &mut writer, opts.flag("skip-bad"), warn_bad, |c| {
+        wanted.iter().all(|(field, value)| c.field(field).is_some_and(|v| v.eq_ignore_ascii_case(value)))
+            && query.as_ref().is_none_or(|q| q.matches(c))
+    })?;
+    writer.commit()?;
+
+    println!("{} of {} customer(s) written , {} bad record(s) skipped", summary.written, summary.read, summary.bad);
+    Ok(())
+}
+
+fn addresses(args: &[String]) -> SectionResult {
+    let (path, by, value) = match args {
+        [path] => (path, None, None),
+        [path, by] => (path, Some(by), None),
+        [path, by, value] => (path, Some(by), Some(value)),
+        _ => return usage_error("usage: rapp1 customers addresses <file> [city|region] [name]"),
+    };
+    let by = match by.map(|b| GroupBy::parse(b)) {
+        None => None,
+        Some(Some(by)) => Some(by),
+        Some(None) => return usage_error("group by 'city' or 'region'"),
+    };
+
+    let (customers, _) = store::read_file(path)?;
+
+    match (by, value) {
+        (Some(by), Some(value)) => {
+            for c in address::filter(&customers, by, value) {
+                println!("{} {} , {}", c.customerid, c.name, c.address);
+            }
+        }
+        (Some(by), None) => {
+            for (key, group) in address::group(&customers, by) {
+                println!("{:<30} {}", key, group.len());
+            }
+        }
+        _ => {
+            let bad = address::report(&customers);
+            for b in &bad {
+                println!("record {} ({}) : '{}' : {}", b.index + 1, b.customerid, b.address, b.error);
+            }
+            println!("\n{} of {} address(es) could not be parsed", bad.len(), customers.len());
+        }
+    }
+    Ok(())
+}
+
+fn phones(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[COUNTRY])?;
+    let path = match &opts.positional[..] {
+        [path] => path,
+        _ => return usage_error("usage: rapp1 customers phones <file> [--country <code>]"),
+    };
+    let country = opts.parsed("country")?;
+
+    let (mut customers, _) = store::read_file(path)?;
+
+    for (number, group) in phone::duplicates(&customers, country) {
+        let ids: Vec<String> = group.iter().map(|c| c.customerid.to_string()).collect();
+        println!("{} is shared by {}", number, ids.join(", "));
+    }
+
+    let bad = phone::normalize_all(&mut customers, country);
+    for b in &bad {
+        println!("record {} ({}) : '{}' {}", b.index + 1, b.customerid, b.phone, b.error);
+    }
+    println!("\n{} of {} phone number(s) could not be normalized", bad.len(), customers.len());
+    Ok(())
+}
+
+fn dedup(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[COUNTRY, ("threshold", true), ("keep", true)])?;
+    let (input, output) = match &opts.positional[..] {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => return usage_error("usage: rapp1 customers dedup <input> [<output>] [--threshold <0..1>] [--keep first|last|most-complete] [--country <code>]"),
+    };
+    let keep = opts.value("keep").unwrap_or("first");
+    let rule = match Survivorship::parse(keep) {
+        Some(rule) => rule,
+        None => return usage_error(format!("unknown rule '{}' , use first, last or most-complete", keep)),
+    };
+    let threshold = opts.parsed("threshold")?.unwrap_or(dedup::DEFAULT_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return usage_error("the threshold is between 0 and 1");
+    }
+
+    let engine = Dedup { threshold, default_country: opts.parsed("country")? };
+    let (customers, _) = store::read_file(input)?;
+    let (merged, clusters) = engine.merge(&customers, rule);
+
+    for (n, cluster) in clusters.iter().enumerate() {
+        println!("cluster {} : {} customer(s)", n + 1, cluster.members.len());
+        for &i in &cluster.members {
+            let c = &customers[i];
+            println!("    record {} ({}) : {} , {} , {}", i + 1, c.customerid, c.name, c.email, c.phone);
+        }
+        for p in &cluster.pairs {
+            let s = &p.score;
+            println!(
+                "    {} ~ {} : {:.2} (name {:.2} , email {:.2} , phone {:.2} , address {:.2})",
+                p.a + 1, p.b + 1, s.total, s.name, s.email, s.phone, s.address
+            );
+        }
+    }
+    println!("\n{} cluster(s) , {} customer(s) -> {} after merging", clusters.len(), customers.len(), merged.len());
+
+    if let Some(output) = output {
+        store::write_file(output, &merged)?;
+        println!("merged customers written to {}", output);
+    }
+    Ok(())
+}
+
+fn diff(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[("format", true)])?;
+    let (old, new) = match &opts.positional[..] {
+        [old, new] => (old, new),
+        _ => return usage_error("usage: rapp1 customers diff <old> <new> [--format text|patch]"),
+    };
+    let patch = match opts.value("format").unwrap_or("text") {
+        "text" => false,
+        "patch" => true,
+        other => return usage_error(format!("unknown format '{}' , use text or patch", other)),
+    };
+
+    let (old, _) = store::read_file(old)?;
+    let (new, _) = store::read_file(new)?;
+    let d = diff::diff(&old, &new);
+
+    if patch {
+        println!("{}", serde_json::to_string_pretty(&d.to_patch())?);
+    } else {
+        println!("{}", d);
+    }
+    Ok(())
+}
+
+// the key of the hash and fake strategies , from the environment so it does not end up in the shell history
+const MASK_KEY: &str = "RAPP1_MASK_KEY";
+
+fn mask(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[
+        COUNTRY, SKIP_BAD, ("name", true), ("email", true), ("phone", true), ("address", true), ("keep-extra", true),
+    ])?;
+    let (input, output) = match &opts.positional[..] {
+        [input, output] => (input, output),
+        _ => return usage_error("usage: rapp1 customers mask <input> <output> [--name <s>] [--email <s>] [--phone <s>] [--address <s>] [--keep-extra <key,key..>] [--country <code>] [--skip-bad]"),
+    };
+
+    let key = std::env::var(MASK_KEY).unwrap_or_default();
+    let mut masker = Masker::new(key.as_bytes());
+    for field in Field::ALL {
+        let name = opts.value(field.name()).unwrap_or("redact");
+        let strategy = match Strategy::parse(name) {
+            Some(strategy) => strategy,
+            None => return usage_error(format!("unknown strategy '{}' for {} , use keep, redact, partial, hash or fake", name, field.name())),
+        };
+        if strategy.is_keyed() && key.is_empty() {
+            return usage_error(format!("--{} {} needs a key , set {}", field.name(), name, MASK_KEY));
+        }
+        masker = masker.with(field, strategy);
+    }
+    if let Some(cc) = opts.parsed("country")? {
+        masker = masker.with_default_country(cc);
+    }
+    for key in opts.value("keep-extra").unwrap_or("").split(',').map(str::trim).filter(|k| !k.is_empty()) {
+        masker = masker.keep_extra(key);
+    }
+
+    let mut writer = CustomerWriter::create(output)?;
+    let summary = for_each_customer(input, opts.flag("skip-bad"), |mut c| {
+        masker.mask(&mut c);
+        writer.write(&c)
+    })?;
+    writer.commit()?;
+
+    println!("masked {} customer(s) into {} , {} bad record(s) skipped", summary.read, output, summary.bad);
+    Ok(())
+}
+
+fn generate(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[("count", true), ("seed", true), COUNTRY])?;
+    let output = match &opts.positional[..] {
+        [output] => output,
+        _ => return usage_error("usage: rapp1 customers generate <output> [--count <n>] [--seed <n>] [--country <code>]"),
+    };
+    let country = opts.parsed("country")?.unwrap_or(NANP);
+    if !is_country_code(country) {
+        return usage_error(format!("--country {} is not a known country code", country));
+    }
+    let count = opts.parsed("count")?.unwrap_or(100);
+    // without a seed every run is different
+    let seed = match opts.parsed("seed")? {
+        Some(seed) => seed,
+        None => rand::random(),
+    };
+
+    let mut writer = CustomerWriter::create(output)?;
+    for c in Generator::new(seed).with_country(country).take(count) {
+        writer.write(&c)?;
+    }
+    writer.commit()?;
+
+    println!("generated {} customer(s) into {} (seed {})", count, output, seed);
+    Ok(())
+}
+
+fn schema(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[("file", false)])?;
+    if !opts.positional.is_empty() {
+        return usage_error("usage: rapp1 customers schema [--file]");
+    }
+    let schema = if opts.flag("file") { jsonschema::file_schema() } else { jsonschema::customer_schema() };
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
+
+/*
+    a JSON document is checked as a whole , an NDJSON file one line at a time.
+    without --schema a JSON array is checked as a file of customers , anything else as one customer
+*/
+fn check(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[("schema", true)])?;
+    let path = match &opts.positional[..] {
+        [path] => path,
+        _ => return usage_error("usage: rapp1 customers check <file> [--schema <schema.json>]"),
+    };
+    let data = std::fs::read_to_string(path)?;
+
+    // (line , document) , the line is None for a JSON document
+    let documents: Vec<(Option<usize>, Value)> = match Format::detect(&data) {
+        Format::Json => vec![(None, serde_json::from_str(&data)?)],
+        _ => {
+            let mut documents = Vec::new();
+            for (i, line) in data.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                let document = serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+                documents.push((Some(i + 1), document));
+            }
+            documents
+        }
+    };
+    let schema = match opts.value("schema") {
+        Some(file) => Schema::new(serde_json::from_str(&std::fs::read_to_string(file)?)?),
+        None if matches!(documents[..], [(None, Value::Array(_))]) => Schema::new(jsonschema::file_schema()),
+        None => Schema::customer(),
+    };
+
+    let mut violations = 0;
+    for (line, document) in &documents {
+        for v in schema.validate(document) {
+            violations += 1;
+            match line {
+                Some(line) => println!("line {} {}", line, v),
+                None => println!("{}", v),
+            }
+        }
+    }
+
+    if violations > 0 {
+        return Err(format!("{} violation(s) of the schema in {}", violations, path).into());
+    }
+    println!("{} : valid ({} document(s))", path, documents.len());
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+fn import(args: &[String]) -> SectionResult {
+    let (db, file) = match args {
+        [db, file] => (db, file),
+        _ => return usage_error("usage: rapp1 customers import <db> <file>"),
+    };
+    let added = SqliteStore::open(db)?.import(file)?;
+    println!("imported {} customer(s) from {} into {}", added, file, db);
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+fn export(args: &[String]) -> SectionResult {
+    let (db, file) = match args {
+        [db, file] => (db, file),
+        _ => return usage_error("usage: rapp1 customers export <db> <file>"),
+    };
+    let written = SqliteStore::open(db)?.export(file)?;
+    println!("exported {} customer(s) from {} into {}", written, db, file);
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+fn lookup(args: &[String]) -> SectionResult {
+    const USAGE: &str = "usage: rapp1 customers lookup <db> (--id <customerid> | --email <email> | --company <name>)";
+    let opts = Opts::parse(args, &[("id", true), ("email", true), ("company", true)])?;
+    let db = match &opts.positional[..] {
+        [db] => SqliteStore::open(db)?,
+        _ => return usage_error(USAGE),
+    };
+    let found = match (opts.parsed::<ObjectId>("id")?, opts.value("email"), opts.value("company")) {
+        (Some(id), None, None) => db.get(&id)?.into_iter().collect(),
+        (None, Some(email), None) => db.by_email(email)?,
+        (None, None, Some(company)) => db.by_company(company)?,
+        _ => return usage_error(USAGE),
+    };
+    println!("{}", serde_json::to_string_pretty(&found)?);
+    Ok(())
+}
+
+fn query(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[
+        ("sort", true), ("page", true), ("size", true), ("offset", true), ("limit", true), ("format", true),
+    ])?;
+    let (path, filter) = match &opts.positional[..] {
+        [path] => (path, None),
+        [path, filter] => (path, Some(filter.parse::<Expr>()?)),
+        _ => return usage_error("usage: rapp1 customers query <file> [<query>] [--sort <keys>] [--page <n> --size <n>] [--offset <n>] [--limit <n>] [--format json|ndjson|csv]"),
+    };
+    let format = match opts.value("format").unwrap_or("json") {
+        "json" => Format::Json,
+        "ndjson" => Format::Ndjson,
+        "csv" => Format::Csv,
+        other => return usage_error(format!("unknown format '{}' , use json, ndjson or csv", other)),
+    };
+
+    let mut q = Query {
+        filter,
+        sort: query::parse_sort(opts.value("sort").unwrap_or(""))?,
+        offset: opts.parsed("offset")?.unwrap_or(0),
+        limit: opts.parsed("limit")?,
+    };
+    if let Some(page) = opts.parsed::<usize>("page")? {
+        if page == 0 {
+            return usage_error("pages start at 1");
+        }
+        q = q.paginate(page, opts.parsed("size")?.unwrap_or(50));
+    }
+
+    let (customers, _) = store::read_file(path)?;
+    let page = q.run(&customers);
+
+    let mut writer = CustomerWriter::new(std::io::stdout().lock(), format);
+    for c in &page.items {
+        writer.write(c)?;
+    }
+    drop(writer.finish()?);
+
+    eprintln!(
+        "{} customer(s) shown , {} matching , {} in total",
+        page.items.len(), page.filtered, page.total
+    );
+    Ok(())
+}
+
+fn stats(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[SKIP_BAD, ("format", true), ("top", true), ("bucket", true)])?;
+    let path = match &opts.positional[..] {
+        [path] => path,
+        _ => return usage_error("usage: rapp1 customers stats <file> [--format text|json] [--top <n>] [--bucket <years>] [--skip-bad]"),
+    };
+    let json = match opts.value("format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        other => return usage_error(format!("unknown format '{}' , use text or json", other)),
+    };
+
+    let mut stats = Stats::new();
+    let summary = for_each_customer(path, opts.flag("skip-bad"), |c| {
+        stats.add(&c);
+        Ok(())
+    })?;
+
+    let report = stats.report(opts.parsed("bucket")?.unwrap_or(10), opts.parsed("top")?.unwrap_or(5));
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report);
+    }
+    if summary.bad > 0 {
+        eprintln!("{} bad record(s) skipped", summary.bad);
+    }
+    Ok(())
+}
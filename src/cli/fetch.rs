@@ -0,0 +1,113 @@
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use crate::net::batch::{self, FetchResult, DEFAULT_CONCURRENCY};
+use crate::net::{self, HttpClient, RetryPolicy};
+use crate::sections::SectionResult;
+
+use super::{usage_error, Opts};
+
+const USAGE: &str = "usage: rapp1 fetch <file|-> [--concurrency <n>] [--timeout <seconds>] [--retries <n>] [--format table|ndjson]";
+
+/*
+    rapp1 fetch <file|-> [--concurrency <n>] [--timeout <seconds>] [--retries <n>] [--format table|ndjson]
+
+    GETs every URL of <file> (one per line , '#' for comments , '-' reads them from stdin) ,
+    8 at a time and with a timeout of 10 seconds per request by default ,
+    and prints the status , latency , bytes and error of each , in the order of the file :
+
+        table  -> a line per URL , then a summary
+        ndjson -> a JSON object per URL , {"url" , "status" , "latency_ms" , "bytes" , "error"} ,
+                  the summary goes to stderr
+
+    the exit code is 1 when a URL failed (no response , or a status of 400 and up)
+*/
+pub fn run(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[("concurrency", true), ("timeout", true), ("retries", true), ("format", true)])?;
+    let path = match &opts.positional[..] {
+        [help] if help == "help" || help == "-h" || help == "--help" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        [path] => path,
+        _ => return usage_error(USAGE),
+    };
+    let concurrency = opts.parsed::<usize>("concurrency")?.unwrap_or(DEFAULT_CONCURRENCY);
+    let timeout = opts.parsed::<f64>("timeout")?.unwrap_or(10.0);
+    let retries = opts.parsed::<u32>("retries")?.unwrap_or(0);
+    let ndjson = match opts.value("format").unwrap_or("table") {
+        "table" => false,
+        "ndjson" => true,
+        other => return usage_error(format!("unknown format '{}' , use table or ndjson", other)),
+    };
+    if concurrency == 0 {
+        return usage_error("--concurrency must be more than 0");
+    }
+    // negative , NaN , too large for a Duration , or so small it rounds to nothing
+    let timeout = match Duration::try_from_secs_f64(timeout) {
+        Ok(timeout) if !timeout.is_zero() => timeout,
+        _ => return usage_error(format!("--timeout must be a number of seconds more than 0 , not {}", timeout)),
+    };
+    // the first attempt and the retries
+    let attempts = match retries.checked_add(1) {
+        Some(attempts) => attempts,
+        None => return usage_error(format!("--retries must be at most {}", u32::MAX - 1)),
+    };
+
+    let text = if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let urls = batch::read_urls(&text);
+
+    let client = HttpClient::builder()
+        .timeout(timeout)
+        .connect_timeout(timeout)
+        .retry(RetryPolicy::default().attempts(attempts))
+        .build()?;
+
+    if !ndjson {
+        println!("{:>6}  {:>9}  {:>9}  URL", "STATUS", "TIME", "BYTES");
+    }
+    let start = Instant::now();
+    let results = net::block_on(batch::fetch_all(&client, &urls, concurrency, |r| {
+        if ndjson {
+            // a FetchResult is plain strings and numbers , it always serializes
+            println!("{}", serde_json::to_string(r).expect("a FetchResult serializes"));
+        } else {
+            println!("{}", table_row(r));
+        }
+    }));
+
+    let failed = results.iter().filter(|r| !r.is_ok()).count();
+    let summary = format!(
+        "{} URL(s) , {} ok , {} failed , {:.1} s , concurrency {}",
+        results.len(),
+        results.len() - failed,
+        failed,
+        start.elapsed().as_secs_f64(),
+        concurrency
+    );
+    if ndjson {
+        eprintln!("{}", summary);
+    } else {
+        println!("{}", summary);
+    }
+    if failed > 0 {
+        return Err(format!("{} of {} URL(s) failed", failed, results.len()).into());
+    }
+    Ok(())
+}
+
+//   200     123 ms       5120  https://example.com/  ,  the error after the URL when there is one
+fn table_row(r: &FetchResult) -> String {
+    let status = r.status.map(|s| s.to_string()).unwrap_or_else(|| String::from("-"));
+    let mut row = format!("{:>6}  {:>6} ms  {:>9}  {}", status, r.latency.as_millis(), r.bytes, r.url);
+    if let Some(e) = &r.error {
+        row.push_str(&format!("  ({})", e));
+    }
+    row
+}
@@ -0,0 +1,118 @@
+use crate::net::joke::{Category, Flag, Joke, JokeClient, JokeQuery, JokeType, Language, BASE_URL, MAX_AMOUNT};
+use crate::net::{self, cache, HttpCache};
+use crate::sections::SectionResult;
+
+use super::{usage_error, Opts};
+
+const USAGE: &str = "usage: rapp1 joke [--category <c,c..>] [--blacklist <flag,flag..>] [--type single|twopart]
+                  [--lang <code>] [--amount <1-10>] [--contains <text>] [--safe] [--url <base url>]
+                  [--cache [--offline]]
+
+    categories : Programming , Misc , Dark , Pun , Spooky , Christmas (none = any)
+    flags      : nsfw , religious , political , racist , sexist , explicit
+    languages  : cs , de , en , es , fr , pt
+
+    --cache keeps the responses on disk (see 'rapp1 cache help') , --offline only answers from there";
+
+/*
+    rapp1 joke [options]
+
+    asks jokeapi.dev (or --url , a server with the same API) for jokes and prints them ,
+    with --cache the same query is answered from the HTTP cache when the API allows it ,
+    with --offline always , without the network.
+    a two part joke with its delivery on a line of its own :
+
+        [Programming #232]
+        Why do programmers prefer dark mode?
+          ... Because light attracts bugs.
+*/
+pub fn run(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(
+        args,
+        &[
+            ("category", true),
+            ("blacklist", true),
+            ("type", true),
+            ("lang", true),
+            ("amount", true),
+            ("contains", true),
+            ("safe", false),
+            ("url", true),
+            ("cache", false),
+            ("offline", false),
+        ],
+    )?;
+    match &opts.positional[..] {
+        [] => {}
+        [help] if help == "help" || help == "-h" || help == "--help" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        _ => return usage_error(USAGE),
+    }
+
+    let mut query = JokeQuery::new();
+    for name in list(opts.value("category")) {
+        // "Any" is the same as no category
+        if !name.eq_ignore_ascii_case("any") {
+            query = query.category(Category::parse(name).ok_or_else(|| invalid("category", name))?);
+        }
+    }
+    for name in list(opts.value("blacklist")) {
+        query = query.blacklist(Flag::parse(name).ok_or_else(|| invalid("blacklist", name))?);
+    }
+    if let Some(name) = opts.value("type") {
+        query = query.joke_type(JokeType::parse(name).ok_or_else(|| invalid("type", name))?);
+    }
+    if let Some(code) = opts.value("lang") {
+        query = query.lang(Language::parse(code).ok_or_else(|| invalid("lang", code))?);
+    }
+    if let Some(amount) = opts.parsed::<u8>("amount")? {
+        if amount == 0 || amount > MAX_AMOUNT {
+            return usage_error(format!("--amount must be 1 to {}", MAX_AMOUNT));
+        }
+        query = query.amount(amount);
+    }
+    if let Some(text) = opts.value("contains") {
+        query = query.contains(text);
+    }
+    if opts.flag("safe") {
+        query = query.safe_mode();
+    }
+
+    let mut http = JokeClient::http_builder(opts.value("url").unwrap_or(BASE_URL));
+    if opts.flag("offline") && !opts.flag("cache") {
+        return usage_error("--offline needs --cache");
+    }
+    if opts.flag("cache") {
+        http = http.cache(HttpCache::new(cache::default_dir()).offline(opts.flag("offline")));
+    }
+    let client = JokeClient::from_http(http.build()?);
+    let jokes = net::block_on(client.jokes(&query))?;
+    let text: Vec<String> = jokes.iter().map(format_joke).collect();
+    println!("{}", text.join("\n\n"));
+    Ok(())
+}
+
+// "a,b , c" -> ["a", "b", "c"]
+fn list(value: Option<&str>) -> impl Iterator<Item = &str> {
+    value.unwrap_or("").split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn invalid(option: &str, value: &str) -> Box<dyn std::error::Error> {
+    Box::new(super::UsageError(format!("invalid value '{}' for --{}\n{}", value, option, USAGE)))
+}
+
+// a header with the category , id and the flags that are set , then the joke
+fn format_joke(joke: &Joke) -> String {
+    let info = joke.info();
+    let mut header = format!("[{} #{}", info.category.name(), info.id);
+    for flag in info.flags.set() {
+        header.push_str(&format!(" , {}", flag.name()));
+    }
+    header.push(']');
+    match joke {
+        Joke::Single { joke, .. } => format!("{}\n{}", header, joke),
+        Joke::TwoPart { setup, delivery, .. } => format!("{}\n{}\n  ... {}", header, setup, delivery),
+    }
+}
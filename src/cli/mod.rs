@@ -0,0 +1,277 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::net::joke::JokeError;
+use crate::net::HttpError;
+use crate::sections::{Section, SECTIONS};
+
+mod cache;
+mod customers;
+mod fetch;
+mod joke;
+mod serve;
+
+/*
+    tools are commands that work on files / data given as arguments ,
+    they are dispatched the same way as the sections , but are not part of 'all'
+*/
+const TOOLS: &[Section] = &[
+    Section { name: "cache", about: "list / clear the on-disk HTTP cache (run 'cache help')", run: cache::run },
+    Section { name: "customers", about: "customer file tools (run 'customers help')", run: customers::run },
+    Section { name: "fetch", about: "GET every URL of a file , concurrently , with status / latency / bytes (run 'fetch help')", run: fetch::run },
+    Section { name: "joke", about: "jokes from jokeapi.dev , by category / type / language (run 'joke help')", run: joke::run },
+    Section { name: "serve", about: "HTTP API over a customer file on localhost (run 'serve help')", run: serve::run },
+];
+
+// a command was called with wrong arguments , reported with exit code 2
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+pub fn usage_error<T>(message: impl Into<String>) -> Result<T, Box<dyn Error>> {
+    Err(Box::new(UsageError(message.into())))
+}
+
+// returns the exit code of the process
+pub fn run(args: &[String]) -> i32 {
+    let program = args.first().map(String::as_str).unwrap_or("rapp1");
+
+    let command = match args.get(1) {
+        Some(c) => c.as_str(),
+        None => {
+            eprint!("{}", usage(program));
+            return 2;
+        }
+    };
+
+    match command {
+        "-h" | "--help" | "help" => {
+            print!("{}", usage(program));
+            0
+        }
+        "list" => {
+            for section in SECTIONS {
+                println!("{}", section.name);
+            }
+            0
+        }
+        "all" => {
+            for section in SECTIONS {
+                if section.name == "http" {
+                    continue; // skipped , it needs the network
+                }
+                println!("\n----------- {} -----------\n", section.name);
+                if let Err(e) = (section.run)(&args[2..]) {
+                    return report(section.name, e);
+                }
+            }
+            0
+        }
+        name => match TOOLS.iter().chain(SECTIONS).find(|s| s.name == name) {
+            Some(section) => match (section.run)(&args[2..]) {
+                Ok(_) => 0,
+                Err(e) => report(section.name, e),
+            },
+            None => {
+                eprintln!("error: unknown command '{}'\n", name);
+                eprint!("{}", usage(program));
+                2
+            }
+        },
+    }
+}
+
+// print the error of a command , and pick the exit code for it
+fn report(name: &str, e: Box<dyn Error>) -> i32 {
+    eprintln!("error: {}: {}", name, e);
+    if e.is::<UsageError>() {
+        2
+    } else if let Some(e) = e.downcast_ref::<HttpError>() {
+        e.exit_code()
+    } else if let Some(e) = e.downcast_ref::<JokeError>() {
+        e.exit_code()
+    } else {
+        1
+    }
+}
+
+pub fn usage(program: &str) -> String {
+    let mut s = format!("usage: {} <command> [args...]\n\ncommands:\n", program);
+    s.push_str("    help       print this message\n");
+    s.push_str("    list       list the names of all sections\n");
+    s.push_str("    all        run every section, except http\n");
+    s.push_str("\ntools:\n");
+    for tool in TOOLS {
+        s.push_str(&format!("    {:<10} {}\n", tool.name, tool.about));
+    }
+    s.push_str("\nsections:\n");
+    for section in SECTIONS {
+        s.push_str(&format!("    {:<10} {}\n", section.name, section.about));
+    }
+    s.push_str("\nexit codes: 0 ok , 1 error , 2 usage , 3-7 failed http requests (see net::HttpError)\n");
+    s
+}
+
+/*
+    Opts -> the arguments of a tool command , split into positional arguments and --options
+
+    the allowed options are given as (name, takes_value) :
+    [("country", true), ("normalize-phones", false)] accepts "--country 1 --normalize-phones"
+*/
+pub struct Opts {
+    pub positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Opts {
+    pub fn parse(args: &[String], allowed: &[(&str, bool)]) -> Result<Opts, Box<dyn Error>> {
+        let mut opts = Opts { positional: Vec::new(), options: Vec::new() };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = match arg.strip_prefix("--") {
+                Some(name) => name,
+                None => {
+                    opts.positional.push(arg.clone());
+                    continue;
+                }
+            };
+            match allowed.iter().find(|(n, _)| *n == name) {
+                Some((_, true)) => match args.next() {
+                    Some(value) => opts.options.push((name.to_string(), Some(value.clone()))),
+                    None => return usage_error(format!("option --{} needs a value", name)),
+                },
+                Some((_, false)) => opts.options.push((name.to_string(), None)),
+                None => return usage_error(format!("unknown option --{}", name)),
+            }
+        }
+        Ok(opts)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+
+    // the last value given for an option
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(n, _)| n == name).and_then(|(_, v)| v.as_deref())
+    }
+
+    // the value of an option , parsed ; a value that does not parse is a usage error
+    pub fn parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, Box<dyn Error>> {
+        match self.value(name) {
+            None => Ok(None),
+            Some(v) => match v.parse() {
+                Ok(t) => Ok(Some(t)),
+                Err(_) => usage_error(format!("invalid value '{}' for --{}", v, name)),
+            },
+        }
+    }
+}
+
+// used by the tools , to print the usage of their own sub-commands
+fn print_commands(tool: &str, commands: &[(&str, &str)]) {
+    println!("usage: rapp1 {} <command> [args...]\n\ncommands:", tool);
+    for (name, about) in commands {
+        println!("    {:<32} {}", name, about);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn args(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cli_help() {
+        assert_eq!(super::run(&args(&["rapp1", "--help"])), 0);
+        assert!(super::usage("rapp1").contains("hashmap"));
+    }
+
+    #[test]
+    fn test_cli_unknown_command() {
+        assert_eq!(super::run(&args(&["rapp1", "no-such-section"])), 2);
+        assert_eq!(super::run(&args(&["rapp1"])), 2);
+    }
+
+    #[test]
+    fn test_cli_section() {
+        assert_eq!(super::run(&args(&["rapp1", "hashmap"])), 0);
+    }
+
+    #[test]
+    fn test_opts() {
+        let allowed = [("country", true), ("normalize-phones", false)];
+        let opts = super::Opts::parse(&args(&["in.json", "--country", "44", "out.csv", "--normalize-phones"]), &allowed).unwrap();
+        assert_eq!(opts.positional, vec!["in.json", "out.csv"]);
+        assert_eq!(opts.parsed::<u16>("country").unwrap(), Some(44));
+        assert!(opts.flag("normalize-phones"));
+
+        assert!(super::Opts::parse(&args(&["--country"]), &allowed).is_err());
+        assert!(super::Opts::parse(&args(&["--verbose"]), &allowed).is_err());
+    }
+
+    #[test]
+    fn test_cli_tool_usage_error() {
+        assert_eq!(super::run(&args(&["rapp1", "customers", "validate"])), 2);
+        assert_eq!(super::run(&args(&["rapp1", "customers", "no-such-command"])), 2);
+    }
+
+    // the options are checked before the file is read , so it does not have to exist
+    #[test]
+    fn test_cli_fetch_out_of_range() {
+        for bad in [&["--timeout", "1e30"][..], &["--timeout", "0"], &["--timeout", "-1"], &["--timeout", "NaN"], &["--retries", "4294967295"], &["--concurrency", "0"]] {
+            let mut argv = args(&["rapp1", "fetch", "no-such-file.txt"]);
+            argv.extend(args(bad));
+            assert_eq!(super::run(&argv), 2, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_cli_generate_country() {
+        let path = std::env::temp_dir().join(format!("rapp1-cli-country-{}.ndjson", std::process::id()));
+        let file = path.to_str().unwrap();
+        for unknown in ["999", "0", "44x"] {
+            assert_eq!(super::run(&args(&["rapp1", "customers", "generate", file, "--country", unknown])), 2, "{}", unknown);
+        }
+        assert!(!path.exists());
+
+        assert_eq!(super::run(&args(&["rapp1", "customers", "generate", file, "--count", "3", "--country", "44"])), 0);
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written.matches("\"phone\":\"+44").count(), 3, "{}", written);
+        std::fs::remove_file(&path).ok();
+    }
+
+    // the output only replaces its file at the end , so it can be the input , and a failure leaves it as it was
+    #[test]
+    fn test_cli_output_replaces_the_file_at_the_end() {
+        let path = std::env::temp_dir().join(format!("rapp1-cli-{}.ndjson", std::process::id()));
+        let file = path.to_str().unwrap();
+        assert_eq!(super::run(&args(&["rapp1", "customers", "generate", file, "--count", "5", "--seed", "1"])), 0);
+        let before = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!(super::run(&args(&["rapp1", "customers", "convert", file, file])), 0);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+        assert_eq!(super::run(&args(&["rapp1", "customers", "filter", file, file, "gender=nobody"])), 0);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+        std::fs::write(&path, &before).unwrap();
+        assert_eq!(super::run(&args(&["rapp1", "customers", "mask", file, file, "--email", "keep"])), 0);
+        let masked = std::fs::read_to_string(&path).unwrap();
+        assert_eq!((masked.lines().count(), masked.contains("[redacted]")), (5, true));
+
+        // a bad record half way , without --skip-bad
+        let broken = format!("{}{{\"customerid\": 5}}\n{}", before, before);
+        std::fs::write(&path, &broken).unwrap();
+        assert_eq!(super::run(&args(&["rapp1", "customers", "convert", file, file])), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use crate::customers::CustomerStore;
+use crate::net;
+use crate::sections::SectionResult;
+use crate::server::{self, State};
+
+use super::{usage_error, Opts};
+
+const USAGE: &str = "usage: rapp1 serve [<file>] [--port <n>]";
+
+/*
+    rapp1 serve [<file>] [--port <n>]
+
+    serves the customers of <file> on http://127.0.0.1:<port> (8080 by default) until Ctrl-C ,
+    changes are saved to the file. without a file the customers only live in memory.
+    http://127.0.0.1:<port>/ is a DataTables page of the customers (see server::datatables) ,
+    bundled into rapp1 with its script and style , it works without network access.
+*/
+pub fn run(args: &[String]) -> SectionResult {
+    let opts = Opts::parse(args, &[("port", true)])?;
+    let store = match &opts.positional[..] {
+        [] => CustomerStore::new(),
+        [help] if help == "help" || help == "-h" || help == "--help" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        [path] => CustomerStore::load(path)?,
+        _ => return usage_error(USAGE),
+    };
+    let port = opts.parsed("port")?.unwrap_or(server::DEFAULT_PORT);
+
+    net::block_on(async {
+        let listener = server::bind(port)?;
+        println!("serving {} customer(s) on http://{}/ , Ctrl-C to stop", store.len(), listener.local_addr()?);
+        let shutdown = async {
+            tokio::signal::ctrl_c().await.ok();
+        };
+        server::serve(listener, Arc::new(State::new(store)), shutdown).await?;
+        Ok(())
+    })
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use crate::model::{Address, AddressError, Customer, ObjectId};
+
+// a customer whose address does not fit "number street, city, region, postal code"
+#[derive(Debug, Clone)]
+pub struct BadAddress {
+    pub index: usize,
+    pub customerid: ObjectId,
+    pub address: String,
+    pub error: AddressError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    City,
+    Region,
+}
+
+impl GroupBy {
+    pub fn parse(s: &str) -> Option<GroupBy> {
+        match s {
+            "city" => Some(GroupBy::City),
+            "region" => Some(GroupBy::Region),
+            _ => None,
+        }
+    }
+
+    pub fn key<'a>(&self, a: &'a Address) -> &'a str {
+        match self {
+            GroupBy::City => &a.city,
+            GroupBy::Region => &a.region,
+        }
+    }
+}
+
+// every address that could not be parsed , in file order
+pub fn report(customers: &[Customer]) -> Vec<BadAddress> {
+    customers
+        .iter()
+        .enumerate()
+        .filter_map(|(index, c)| match c.parsed_address() {
+            Ok(_) => None,
+            Err(error) => Some(BadAddress {
+                index,
+                customerid: c.customerid,
+                address: c.address.clone(),
+                error,
+            }),
+        })
+        .collect()
+}
+
+// customers grouped by city or region , sorted by name of the group ; bad addresses are left out
+pub fn group(customers: &[Customer], by: GroupBy) -> BTreeMap<String, Vec<&Customer>> {
+    let mut groups: BTreeMap<String, Vec<&Customer>> = BTreeMap::new();
+    for c in customers {
+        if let Ok(a) = c.parsed_address() {
+            groups.entry(by.key(&a).to_string()).or_default().push(c);
+        }
+    }
+    groups
+}
+
+// customers whose city or region is 'value' (ignoring case)
+pub fn filter<'a>(customers: &'a [Customer], by: GroupBy, value: &str) -> Vec<&'a Customer> {
+    customers
+        .iter()
+        .filter(|c| match c.parsed_address() {
+            Ok(a) => by.key(&a).eq_ignore_ascii_case(value),
+            Err(_) => false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str, address: &str) -> Customer {
+        Customer {
+            address: address.to_string(),
+            ..Customer::sample(id, "Mabel Haley", 28)
+        }
+    }
+
+    #[test]
+    fn test_report_group_filter() {
+        let customers = vec![
+            customer("a", "184 Bergen Court, Gorham, American Samoa, 8722"),
+            customer("b", "somewhere"),
+            customer("c", "741 Dahill Road, Tyro, Nevada, 3052"),
+            customer("d", "12 Main Street, Gorham, Nevada, 1000"),
+        ];
+
+        let bad = report(&customers);
+        assert_eq!(bad.len(), 1);
+        assert_eq!(bad[0].customerid, oid("b"));
+        assert_eq!(bad[0].error, AddressError::Parts(1));
+
+        let by_city = group(&customers, GroupBy::City);
+        assert_eq!(by_city.keys().collect::<Vec<_>>(), vec!["Gorham", "Tyro"]);
+        assert_eq!(by_city["Gorham"].len(), 2);
+
+        let nevada = filter(&customers, GroupBy::Region, "nevada");
+        assert_eq!(nevada.iter().map(|c| c.customerid).collect::<Vec<_>>(), vec![oid("c"), oid("d")]);
+    }
+}
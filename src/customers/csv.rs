@@ -0,0 +1,188 @@
+use std::fmt;
+use std::io::{Read, Write};
+
+use crate::model::Customer;
+
+// the columns , in the order they are written
+pub const FIELDS: &[&str] = Customer::FIELDS;
+
+/*
+    header mapping : a header is matched to a Customer field after
+    lower-casing it and dropping everything that is not a letter or digit,
+    so "Customer ID", "customer_id" and "customerId" all map to customerid.
+    Columns that do not map to any field are ignored.
+*/
+fn field_for_header(header: &str) -> Option<usize> {
+    let key: String = header.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+    let field = match key.as_str() {
+        "id" | "customerid" | "customer" => "customerid",
+        "eyecolor" | "eyecolour" | "eyes" => "eyecolor",
+        "fullname" => "name",
+        "sex" => "gender",
+        "emailaddress" | "mail" => "email",
+        "phonenumber" | "telephone" | "tel" => "phone",
+        other => other,
+    };
+    FIELDS.iter().position(|f| *f == field)
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Csv(::csv::Error),
+    // the header row is missing one or more of the Customer fields
+    MissingColumns(Vec<&'static str>),
+    // a value that could not be converted , row is the line number in the file
+    Field { row: u64, column: usize, field: &'static str, message: String },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(e) => write!(f, "{}", e),
+            CsvError::MissingColumns(fields) => write!(f, "header is missing column(s): {}", fields.join(", ")),
+            CsvError::Field { row, column, field, message } => {
+                write!(f, "row {}, column {} ({}): {}", row, column, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<::csv::Error> for CsvError {
+    fn from(e: ::csv::Error) -> Self {
+        CsvError::Csv(e)
+    }
+}
+
+// read customers from CSV , the first row must be a header
+pub fn read_customers<R: Read>(r: R) -> Result<Vec<Customer>, CsvError> {
+    let mut reader = ::csv::ReaderBuilder::new().trim(::csv::Trim::All).from_reader(r);
+
+    // columns[field] -> position of that field in a row
+    let mut columns: [Option<usize>; 9] = [None; 9];
+    for (i, header) in reader.headers()?.iter().enumerate() {
+        if let Some(field) = field_for_header(header) {
+            columns[field].get_or_insert(i);
+        }
+    }
+    let missing: Vec<&'static str> = FIELDS.iter().zip(columns.iter()).filter(|(_, c)| c.is_none()).map(|(f, _)| *f).collect();
+    if !missing.is_empty() {
+        return Err(CsvError::MissingColumns(missing));
+    }
+    let columns: Vec<usize> = columns.iter().flatten().copied().collect();
+
+    let mut customers = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let row = record.position().map(|p| p.line()).unwrap_or(0);
+        let value = |field: usize| record.get(columns[field]).unwrap_or("").to_string();
+
+        // an empty cell is no age
+        let age = match value(1).trim() {
+            "" => None,
+            text => Some(text.parse::<u32>().map_err(|e| CsvError::Field {
+                row,
+                column: columns[1] + 1,
+                field: FIELDS[1],
+                message: format!("'{}' is not a valid age : {}", text, e),
+            })?),
+        };
+
+        let customerid = value(0).parse().map_err(|e| CsvError::Field {
+            row,
+            column: columns[0] + 1,
+            field: FIELDS[0],
+            message: format!("'{}' is not a valid customerid : {}", value(0), e),
+        })?;
+
+        customers.push(Customer {
+            customerid,
+            age,
+            eyecolor: value(2),
+            name: value(3),
+            gender: value(4),
+            company: value(5),
+            email: value(6),
+            phone: value(7),
+            address: value(8),
+            extra: Default::default(),
+        });
+    }
+    Ok(customers)
+}
+
+/*
+    one row per customer , the header row goes before the first one.
+    CSV has a fixed set of columns , so the extra keys of a customer are not written.
+*/
+pub fn write_row<W: Write>(writer: &mut ::csv::Writer<W>, c: &Customer, first: bool) -> Result<(), ::csv::Error> {
+    if first {
+        writer.write_record(FIELDS)?;
+    }
+    writer.write_record(FIELDS.iter().map(|f| c.field(f).unwrap_or_default()))
+}
+
+// write customers as CSV with a header row , fields containing commas or quotes get quoted
+pub fn write_customers<W: Write>(w: W, customers: &[Customer]) -> Result<(), CsvError> {
+    let mut writer = ::csv::Writer::from_writer(w);
+    for (i, c) in customers.iter().enumerate() {
+        write_row(&mut writer, c, i == 0)?;
+    }
+    writer.flush().map_err(::csv::Error::from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mabel() -> Customer {
+        Customer::sample("630c2272eabd3d30fe44d139", "Mabel \"May\" Haley", 28)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut out = Vec::new();
+        write_customers(&mut out, &[mabel()]).unwrap();
+        let text = String::from_utf8(out.clone()).unwrap();
+        assert!(text.starts_with("customerid,age,eyecolor,name,"));
+        assert!(text.contains("\"184 Bergen Court, Gorham, American Samoa, 8722\""));
+
+        assert_eq!(read_customers(out.as_slice()).unwrap(), vec![mabel()]);
+    }
+
+    #[test]
+    fn test_header_mapping() {
+        let data = "Email Address,Customer ID,Age,Eye Color,Full Name,Sex,Company,Phone Number,Address,Notes\n\
+                    a@b.com,630c2272eabd3d30fe44d139,30,blue,Ann Lee,female,ACME,555-1234,\"1 Main St, Town, State, 1\",x\n";
+        let customers = read_customers(data.as_bytes()).unwrap();
+        assert_eq!(customers[0].customerid.to_string(), "630c2272eabd3d30fe44d139");
+        assert_eq!(customers[0].email, "a@b.com");
+        assert_eq!(customers[0].address, "1 Main St, Town, State, 1");
+    }
+
+    #[test]
+    fn test_errors() {
+        let missing = "customerid,age\nabc,30\n";
+        match read_customers(missing.as_bytes()) {
+            Err(CsvError::MissingColumns(fields)) => assert_eq!(fields.len(), 7),
+            other => panic!("unexpected : {:?}", other),
+        }
+
+        let bad_age = format!("{}\n630c2272eabd3d30fe44d139,30,blue,Ann,female,ACME,a@b.com,555,addr\n630c2272eabd3d30fe44d139,old,blue,Ann,female,ACME,a@b.com,555,addr\n", FIELDS.join(","));
+        let e = read_customers(bad_age.as_bytes()).unwrap_err();
+        assert!(e.to_string().starts_with("row 3, column 2 (age): 'old' is not a valid age"), "{}", e);
+
+        let bad_id = format!("{}\nabc,30,blue,Ann,female,ACME,a@b.com,555,addr\n", FIELDS.join(","));
+        let e = read_customers(bad_id.as_bytes()).unwrap_err();
+        assert!(e.to_string().starts_with("row 2, column 1 (customerid): 'abc' is not a valid customerid"), "{}", e);
+    }
+}
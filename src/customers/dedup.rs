@@ -0,0 +1,449 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::model::{Customer, PhoneNumber};
+
+/*
+    Duplicate detection :
+
+    1. blocking  -> customers are put in blocks by email , phone , postal code and a name key ;
+                    only customers that share a block become candidate pairs
+                    (comparing every customer with every other one does not scale)
+    2. scoring   -> every candidate pair gets a score between 0 and 1 , a weighted sum of
+                    name , email , phone and address similarity
+    3. grouping  -> pairs scoring at least the threshold are joined into clusters
+    4. merging   -> every cluster is replaced by one customer , picked by the survivorship rule ,
+                    with its empty fields filled in from the other members
+*/
+
+pub const NAME_WEIGHT: f64 = 0.4;
+pub const EMAIL_WEIGHT: f64 = 0.25;
+pub const PHONE_WEIGHT: f64 = 0.2;
+pub const ADDRESS_WEIGHT: f64 = 0.15;
+
+pub const DEFAULT_THRESHOLD: f64 = 0.75;
+
+// blocks bigger than this are skipped , they would only give pairs of very common values
+pub const MAX_BLOCK: usize = 500;
+
+// ------------- similarity -------------
+
+// Jaro-Winkler similarity , 1.0 for equal strings , 0.0 for nothing in common
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+
+    for i in 0..a.len() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && a[i] == b[j] {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, &m)| m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, &m)| m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+// lower case words , without punctuation
+fn words(s: &str) -> Vec<String> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// "Mabel Haley" , "haley, mabel" and "Mabel  Hailey" are all close
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (wa, wb) = (words(a), words(b));
+    let straight = jaro_winkler(&wa.join(" "), &wb.join(" "));
+
+    let (mut sa, mut sb) = (wa, wb);
+    sa.sort();
+    sb.sort();
+    straight.max(jaro_winkler(&sa.join(" "), &sb.join(" ")))
+}
+
+// lower case , and without a +tag : "Mabel.Haley+shop@Enomen.com" -> "mabel.haley@enomen.com"
+pub fn normalize_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let local = local.split('+').next().unwrap_or(local);
+            format!("{}@{}", local, domain)
+        }
+        None => email,
+    }
+}
+
+fn email_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_email(a), normalize_email(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    // same mailbox name at another domain still says something
+    match (a.split_once('@'), b.split_once('@')) {
+        (Some((la, _)), Some((lb, _))) => 0.8 * jaro_winkler(la, lb),
+        _ => 0.0,
+    }
+}
+
+fn address_similarity(a: &str, b: &str) -> f64 {
+    match (a.parse::<crate::model::Address>(), b.parse::<crate::model::Address>()) {
+        (Ok(x), Ok(y)) => {
+            let same_number = if x.street_number.eq_ignore_ascii_case(&y.street_number) { 1.0 } else { 0.0 };
+            let same_postal = if x.postal_code == y.postal_code { 1.0 } else { 0.0 };
+            0.3 * same_number + 0.4 * jaccard(&words(&x.street), &words(&y.street)) + 0.3 * same_postal
+        }
+        // not parsed , compare the words of the whole address
+        _ => jaccard(&words(a), &words(b)),
+    }
+}
+
+fn jaccard(a: &[String], b: &[String]) -> f64 {
+    let a: BTreeSet<&String> = a.iter().collect();
+    let b: BTreeSet<&String> = b.iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+// ------------- scoring -------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub name: f64,
+    pub email: f64,
+    pub phone: f64,
+    pub address: f64,
+    pub total: f64,
+}
+
+/*
+    the customer fields that take part in matching , normalized once per customer
+    and not once per pair
+*/
+struct Prepared {
+    phone: Option<String>,
+    email: String,
+    name_key: String,
+    postal_code: Option<String>,
+}
+
+impl Prepared {
+    fn new(c: &Customer, default_country: Option<u16>) -> Prepared {
+        let name = words(&c.name);
+        // first letter of the first name , and the first 4 letters of the last name
+        let name_key = match (name.first(), name.last()) {
+            (Some(first), Some(last)) => first.chars().take(1).chain(last.chars().take(4)).collect(),
+            _ => String::new(),
+        };
+        Prepared {
+            phone: PhoneNumber::parse(&c.phone, default_country).ok().map(|p| p.e164()),
+            email: normalize_email(&c.email),
+            name_key,
+            postal_code: c.parsed_address().ok().map(|a| a.postal_code),
+        }
+    }
+}
+
+pub fn score(a: &Customer, b: &Customer, default_country: Option<u16>) -> Score {
+    score_prepared(a, &Prepared::new(a, default_country), b, &Prepared::new(b, default_country))
+}
+
+fn score_prepared(a: &Customer, pa: &Prepared, b: &Customer, pb: &Prepared) -> Score {
+    let name = name_similarity(&a.name, &b.name);
+    let email = email_similarity(&a.email, &b.email);
+    let phone = match (&pa.phone, &pb.phone) {
+        (Some(x), Some(y)) if x == y => 1.0,
+        _ => 0.0,
+    };
+    let address = address_similarity(&a.address, &b.address);
+    let total = NAME_WEIGHT * name + EMAIL_WEIGHT * email + PHONE_WEIGHT * phone + ADDRESS_WEIGHT * address;
+    Score { name, email, phone, address, total }
+}
+
+// ------------- pairs and clusters -------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair {
+    // positions in the list of customers , a < b
+    pub a: usize,
+    pub b: usize,
+    pub score: Score,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    // positions in the list of customers , in file order
+    pub members: Vec<usize>,
+    // the pairs that joined this cluster
+    pub pairs: Vec<Pair>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Survivorship {
+    // the member that comes first in the file
+    First,
+    // the member that comes last in the file
+    Last,
+    // the member with the most non-empty fields
+    MostComplete,
+}
+
+impl Survivorship {
+    pub fn parse(s: &str) -> Option<Survivorship> {
+        match s {
+            "first" => Some(Survivorship::First),
+            "last" => Some(Survivorship::Last),
+            "most-complete" => Some(Survivorship::MostComplete),
+            _ => None,
+        }
+    }
+}
+
+pub struct Dedup {
+    pub threshold: f64,
+    pub default_country: Option<u16>,
+}
+
+impl Default for Dedup {
+    fn default() -> Self {
+        Dedup { threshold: DEFAULT_THRESHOLD, default_country: None }
+    }
+}
+
+impl Dedup {
+    // every pair of customers that share at least one block , scored , best first
+    pub fn pairs(&self, customers: &[Customer]) -> Vec<Pair> {
+        let prepared: Vec<Prepared> = customers.iter().map(|c| Prepared::new(c, self.default_country)).collect();
+
+        let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, p) in prepared.iter().enumerate() {
+            let keys = [
+                Some(format!("email:{}", p.email)).filter(|_| !p.email.is_empty()),
+                p.phone.as_ref().map(|x| format!("phone:{}", x)),
+                p.postal_code.as_ref().map(|x| format!("postal:{}", x)),
+                Some(format!("name:{}", p.name_key)).filter(|_| !p.name_key.is_empty()),
+            ];
+            for key in keys.into_iter().flatten() {
+                blocks.entry(key).or_default().push(i);
+            }
+        }
+
+        let mut candidates = BTreeSet::new();
+        for members in blocks.values().filter(|m| m.len() > 1 && m.len() <= MAX_BLOCK) {
+            for (x, &a) in members.iter().enumerate() {
+                for &b in &members[x + 1..] {
+                    candidates.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        let mut pairs: Vec<Pair> = candidates
+            .into_iter()
+            .map(|(a, b)| Pair { a, b, score: score_prepared(&customers[a], &prepared[a], &customers[b], &prepared[b]) })
+            .filter(|p| p.score.total >= self.threshold)
+            .collect();
+        pairs.sort_by(|x, y| y.score.total.total_cmp(&x.score.total).then((x.a, x.b).cmp(&(y.a, y.b))));
+        pairs
+    }
+
+    // likely duplicates , grouped ; customers without a duplicate are not in any cluster
+    pub fn clusters(&self, customers: &[Customer]) -> Vec<Cluster> {
+        let pairs = self.pairs(customers);
+
+        // union-find over the positions
+        let mut parent: Vec<usize> = (0..customers.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for p in &pairs {
+            let (ra, rb) = (root(&mut parent, p.a), root(&mut parent, p.b));
+            if ra != rb {
+                parent[ra.max(rb)] = ra.min(rb);
+            }
+        }
+
+        let mut by_root: HashMap<usize, Cluster> = HashMap::new();
+        for p in pairs {
+            let r = root(&mut parent, p.a);
+            by_root.entry(r).or_insert_with(|| Cluster { members: Vec::new(), pairs: Vec::new() }).pairs.push(p);
+        }
+        for i in 0..customers.len() {
+            let r = root(&mut parent, i);
+            if let Some(cluster) = by_root.get_mut(&r) {
+                cluster.members.push(i);
+            }
+        }
+
+        let mut clusters: Vec<Cluster> = by_root.into_values().collect();
+        clusters.sort_by_key(|c| c.members[0]);
+        clusters
+    }
+
+    /*
+        the customers with every cluster replaced by one merged customer ,
+        which takes the place of the first member of its cluster
+    */
+    pub fn merge(&self, customers: &[Customer], rule: Survivorship) -> (Vec<Customer>, Vec<Cluster>) {
+        let clusters = self.clusters(customers);
+
+        let mut replaced: HashMap<usize, Option<Customer>> = HashMap::new();
+        for cluster in &clusters {
+            let merged = merge_cluster(customers, &cluster.members, rule);
+            for (n, &i) in cluster.members.iter().enumerate() {
+                replaced.insert(i, if n == 0 { Some(merged.clone()) } else { None });
+            }
+        }
+
+        let merged = customers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match replaced.get(&i) {
+                Some(m) => m.clone(),
+                None => Some(c.clone()),
+            })
+            .collect();
+        (merged, clusters)
+    }
+}
+
+fn filled_fields(c: &Customer) -> usize {
+    Customer::FIELDS.iter().filter(|f| c.field(f).is_some_and(|v| !v.trim().is_empty())).count()
+}
+
+fn merge_cluster(customers: &[Customer], members: &[usize], rule: Survivorship) -> Customer {
+    let survivor = match rule {
+        Survivorship::First => members[0],
+        Survivorship::Last => members[members.len() - 1],
+        // max_by_key returns the last of equal elements , so go through the members in reverse
+        Survivorship::MostComplete => *members.iter().rev().max_by_key(|&&i| filled_fields(&customers[i])).unwrap_or(&members[0]),
+    };
+
+    let mut merged = customers[survivor].clone();
+    for &i in members {
+        let other = &customers[i];
+        let fill = |mine: &mut String, theirs: &String| {
+            if mine.trim().is_empty() && !theirs.trim().is_empty() {
+                *mine = theirs.clone();
+            }
+        };
+        fill(&mut merged.eyecolor, &other.eyecolor);
+        fill(&mut merged.name, &other.name);
+        fill(&mut merged.gender, &other.gender);
+        fill(&mut merged.company, &other.company);
+        fill(&mut merged.email, &other.email);
+        fill(&mut merged.phone, &other.phone);
+        fill(&mut merged.address, &other.address);
+        merged.age = merged.age.or(other.age);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ObjectId;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str, name: &str, email: &str, phone: &str, address: &str) -> Customer {
+        Customer {
+            email: email.to_string(),
+            phone: phone.to_string(),
+            address: address.to_string(),
+            ..Customer::sample(id, name, 28)
+        }
+    }
+
+    fn sample() -> Vec<Customer> {
+        vec![
+            customer("a", "Mabel Haley", "mabelhaley@enomen.com", "+1 (880) 516-2365", "184 Bergen Court, Gorham, American Samoa, 8722"),
+            customer("b", "Whitney Carver", "whitneycarver@zillacon.com", "+1 (904) 443-2818", "741 Dahill Road, Tyro, Nevada, 3052"),
+            customer("c", "Mabel Hailey", "MabelHaley+shop@enomen.com", "880.516.2365", "184 Bergen Ct, Gorham, American Samoa, 8722"),
+            customer("d", "Haley, Mabel", "", "+18805162365", "184 Bergen Court, Gorham, American Samoa, 8722"),
+            customer("e", "Lara Mcleod", "laramcleod@enomen.com", "+1 (829) 420-2707", "377 Kathleen Court, Cazadero, Georgia, 4297"),
+        ]
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(jaro_winkler("martha", "martha"), 1.0);
+        assert!((jaro_winkler("martha", "marhta") - 0.961).abs() < 0.001);
+        assert!((jaro_winkler("dixon", "dicksonx") - 0.813).abs() < 0.001);
+        assert_eq!(jaro_winkler("abc", "xyz"), 0.0);
+
+        assert_eq!(name_similarity("Mabel Haley", "haley, MABEL"), 1.0);
+        assert!(name_similarity("Mabel Haley", "Lara Mcleod") < name_similarity("Mabel Haley", "Mabel Hailey"));
+        assert!(name_similarity("Mabel Haley", "Lara Mcleod") < 0.7);
+        assert_eq!(normalize_email(" Mabel.Haley+shop@Enomen.com"), "mabel.haley@enomen.com");
+    }
+
+    #[test]
+    fn test_clusters() {
+        let customers = sample();
+        let dedup = Dedup { threshold: DEFAULT_THRESHOLD, default_country: Some(1) };
+        let clusters = dedup.clusters(&customers);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, vec![0, 2, 3]);
+        assert!(clusters[0].pairs.iter().all(|p| p.score.total >= DEFAULT_THRESHOLD));
+
+        // without a default country "880.516.2365" does not parse , so the phones of a and c differ
+        let s = score(&customers[0], &customers[2], None);
+        assert_eq!((s.email, s.phone), (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_merge() {
+        let customers = sample();
+        let dedup = Dedup { threshold: DEFAULT_THRESHOLD, default_country: Some(1) };
+
+        let (merged, _) = dedup.merge(&customers, Survivorship::Last);
+        let ids: Vec<ObjectId> = merged.iter().map(|c| c.customerid).collect();
+        assert_eq!(ids, vec![oid("d"), oid("b"), oid("e")]);
+        // d has no email , it is filled in from the other members
+        assert_eq!(merged[0].email, "mabelhaley@enomen.com");
+
+        let (merged, _) = dedup.merge(&customers, Survivorship::MostComplete);
+        assert_eq!(merged[0].customerid, oid("a"));
+        assert_eq!(merged.len(), 3);
+    }
+}
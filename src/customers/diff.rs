@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::model::{Customer, ObjectId};
+
+/*
+    the difference between two snapshots of the customers (yesterday's and today's export) ,
+    matched by customerid :
+
+        added   -> only in the new snapshot
+        removed -> only in the old snapshot
+        changed -> in both , with at least one field that is not the same
+*/
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+// email: old -> new
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.old, self.new)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub customerid: ObjectId,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Diff {
+    // in the order of the new snapshot
+    pub added: Vec<Customer>,
+    // in the order of the old snapshot
+    pub removed: Vec<Customer>,
+    // in the order of the new snapshot
+    pub changed: Vec<Change>,
+}
+
+// the field changes between two versions of one customer , in the order of Customer::FIELDS
+pub fn changes(old: &Customer, new: &Customer) -> Vec<FieldChange> {
+    Customer::FIELDS
+        .iter()
+        .filter_map(|&field| {
+            let (o, n) = (old.field(field).unwrap_or_default(), new.field(field).unwrap_or_default());
+            if o == n {
+                None
+            } else {
+                Some(FieldChange { field, old: o, new: n })
+            }
+        })
+        .collect()
+}
+
+// when an id is in a snapshot more than once , its first record is used
+pub fn diff(old: &[Customer], new: &[Customer]) -> Diff {
+    let mut old_by_id: HashMap<ObjectId, &Customer> = HashMap::new();
+    for c in old {
+        old_by_id.entry(c.customerid).or_insert(c);
+    }
+    let mut new_by_id: HashMap<ObjectId, &Customer> = HashMap::new();
+    for c in new {
+        new_by_id.entry(c.customerid).or_insert(c);
+    }
+
+    let mut d = Diff::default();
+    for c in new {
+        // only the record that went into the map , so duplicate ids are reported once
+        if !std::ptr::eq(new_by_id[&c.customerid], c) {
+            continue;
+        }
+        match old_by_id.get(&c.customerid) {
+            None => d.added.push(c.clone()),
+            Some(before) => {
+                let fields = changes(before, c);
+                if !fields.is_empty() {
+                    d.changed.push(Change { customerid: c.customerid, fields });
+                }
+            }
+        }
+    }
+    for c in old {
+        if std::ptr::eq(old_by_id[&c.customerid], c) && !new_by_id.contains_key(&c.customerid) {
+            d.removed.push(c.clone());
+        }
+    }
+    d
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /*
+        the diff as a JSON patch (RFC 6902) , over a document that is an object of
+        the customers by customerid :
+
+            { "op": "remove",  "path": "/630c2272eabd3d30fe44d139" }
+            { "op": "add",     "path": "/630c2272eabd3d30fe44d140", "value": { ... } }
+            { "op": "replace", "path": "/630c2272eabd3d30fe44d141/email", "value": "..." }
+    */
+    pub fn to_patch(&self) -> Value {
+        let mut ops = Vec::new();
+        for c in &self.removed {
+            ops.push(json!({ "op": "remove", "path": format!("/{}", c.customerid) }));
+        }
+        for c in &self.added {
+            ops.push(json!({ "op": "add", "path": format!("/{}", c.customerid), "value": c }));
+        }
+        for change in &self.changed {
+            for fc in &change.fields {
+                // keep age a number
+                let value = match fc.new.parse::<u32>() {
+                    Ok(n) if fc.field == "age" => json!(n),
+                    _ => json!(fc.new),
+                };
+                let path = format!("/{}/{}", change.customerid, fc.field);
+                ops.push(json!({ "op": "replace", "path": path, "value": value }));
+            }
+        }
+        Value::Array(ops)
+    }
+}
+
+// the human readable report
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.added {
+            writeln!(f, "+ {} ({})", c.customerid, c.name)?;
+        }
+        for c in &self.removed {
+            writeln!(f, "- {} ({})", c.customerid, c.name)?;
+        }
+        for change in &self.changed {
+            writeln!(f, "~ {}", change.customerid)?;
+            for fc in &change.fields {
+                writeln!(f, "    {}", fc)?;
+            }
+        }
+        write!(f, "{} added , {} removed , {} changed", self.added.len(), self.removed.len(), self.changed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str, name: &str, email: &str, age: u32) -> Customer {
+        Customer {
+            email: email.to_string(),
+            ..Customer::sample(id, name, age)
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = vec![
+            customer("a", "Mabel Haley", "mabelhaley@enomen.com", 28),
+            customer("b", "Whitney Carver", "whitneycarver@zillacon.com", 35),
+            customer("c", "Lara Mcleod", "laramcleod@enomen.com", 41),
+        ];
+        let new = vec![
+            customer("c", "Lara Mcleod", "laramcleod@enomen.com", 41),
+            customer("a", "Mabel Haley", "mabel@enomen.com", 29),
+            customer("d", "Hodges Pruitt", "hodgespruitt@geekola.com", 23),
+        ];
+
+        let d = diff(&old, &new);
+        assert_eq!(d.added.iter().map(|c| c.customerid).collect::<Vec<_>>(), vec![oid("d")]);
+        assert_eq!(d.removed.iter().map(|c| c.customerid).collect::<Vec<_>>(), vec![oid("b")]);
+        assert_eq!(d.changed.len(), 1);
+        let fields: Vec<String> = d.changed[0].fields.iter().map(|fc| fc.to_string()).collect();
+        assert_eq!(fields, vec!["age: 28 -> 29", "email: mabelhaley@enomen.com -> mabel@enomen.com"]);
+
+        let text = d.to_string();
+        assert!(text.contains("+ 00000000000000000000000d (Hodges Pruitt)"));
+        assert!(text.contains("- 00000000000000000000000b (Whitney Carver)"));
+        assert!(text.ends_with("1 added , 1 removed , 1 changed"));
+
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_patch() {
+        let old = vec![customer("a1", "Mabel Haley", "mabelhaley@enomen.com", 28), customer("b", "Whitney Carver", "", 35)];
+        let new = vec![customer("a1", "Mabel Haley", "mabelhaley@enomen.com", 29), customer("d", "Hodges Pruitt", "", 23)];
+
+        let patch = diff(&old, &new).to_patch();
+        assert_eq!(patch[0], json!({ "op": "remove", "path": "/00000000000000000000000b" }));
+        assert_eq!(patch[1]["op"], "add");
+        assert_eq!(patch[1]["value"]["name"], "Hodges Pruitt");
+        assert_eq!(patch[2], json!({ "op": "replace", "path": "/0000000000000000000000a1/age", "value": 29 }));
+    }
+}
@@ -0,0 +1,120 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::model::NANP;
+
+/*
+    plausible made up values for customer fields ,
+    used in place of real ones when customer data is masked
+*/
+
+pub const FIRST_NAMES: &[&str] = &[
+    "Mabel", "Whitney", "Hodges", "Lara", "Vance", "Estella", "Alba", "Barlow", "Bettie", "Bird",
+    "Carmela", "Castro", "Dolores", "Dunlap", "Elsa", "Fitzgerald", "Gail", "Hester", "Ingram", "Jenna",
+    "Kirby", "Leila", "Marsh", "Nadia", "Odom", "Pearl", "Quinn", "Rosalind", "Sharp", "Tania",
+];
+
+pub const LAST_NAMES: &[&str] = &[
+    "Haley", "Carver", "Pruitt", "Mcleod", "Mays", "Roth", "Ayers", "Bishop", "Booker", "Cantu",
+    "Dalton", "Ellison", "Frost", "Gates", "Hardy", "Irwin", "Jacobs", "Kemp", "Lowe", "Mercer",
+    "Nash", "Oneal", "Potts", "Rivas", "Stone", "Tate", "Underwood", "Velez", "Webb", "York",
+];
+
+pub const COMPANIES: &[&str] = &[
+    "ENOMEN", "ZILLACON", "GEEKOLA", "ACCUPRINT", "BIOSPAN", "COMTRAIL", "DIGIGEN", "EXOSPACE", "FLUMBO", "GLUKGLUK",
+    "HOTCAKES", "ISOLOGIA", "JUMPSTACK", "KONGENE", "LUNCHPAD", "MEDICROIX", "NETPLAX", "OPTICALL", "PORTALIS", "QUILTIGEN",
+];
+
+pub const STREETS: &[&str] = &[
+    "Bergen Court", "Dahill Road", "Kathleen Court", "Cortelyou Road", "Amber Street", "Bay Parkway", "Calder Place",
+    "Dewitt Avenue", "Eldert Lane", "Flatlands Avenue", "Garden Place", "Hampton Place", "Irving Street", "Jefferson Street",
+    "Kent Street", "Lott Avenue", "Montague Terrace", "Norman Avenue", "Oriental Court", "Pioneer Street",
+];
+
+pub const CITIES: &[&str] = &[
+    "Gorham", "Tyro", "Cazadero", "Bowmansville", "Chesapeake", "Dexter", "Elwood", "Falconaire", "Glenville", "Hatteras",
+    "Iberia", "Jacksonwald", "Kenmar", "Lindisfarne", "Marienthal", "Nettie", "Oceola", "Primrose", "Rockbridge", "Sunwest",
+];
+
+pub const REGIONS: &[&str] = &[
+    "American Samoa", "Nevada", "Georgia", "Ohio", "Virginia", "Maine", "Oregon", "Texas", "Utah", "Vermont",
+    "Florida", "Idaho", "Kansas", "Montana", "Wyoming", "Alabama", "Iowa", "Colorado", "Kentucky", "Arizona",
+];
+
+fn pick<'a, R: Rng + ?Sized>(rng: &mut R, values: &[&'a str]) -> &'a str {
+    values.choose(rng).copied().unwrap_or_default()
+}
+
+pub fn first_name<R: Rng + ?Sized>(rng: &mut R) -> &'static str {
+    pick(rng, FIRST_NAMES)
+}
+
+pub fn last_name<R: Rng + ?Sized>(rng: &mut R) -> &'static str {
+    pick(rng, LAST_NAMES)
+}
+
+pub fn company<R: Rng + ?Sized>(rng: &mut R) -> &'static str {
+    pick(rng, COMPANIES)
+}
+
+// "Mabel Haley"
+pub fn name<R: Rng + ?Sized>(rng: &mut R) -> String {
+    format!("{} {}", first_name(rng), last_name(rng))
+}
+
+// "Mabel Haley" , "ENOMEN" -> "mabelhaley@enomen.com"
+pub fn email(name: &str, company: &str) -> String {
+    let local: String = name.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    format!("{}@{}.com", local.to_lowercase(), company.to_lowercase())
+}
+
+// "+18805162365" , a valid NANP number in E.164 : area code and exchange do not start with 0 or 1
+pub fn phone<R: Rng + ?Sized>(rng: &mut R) -> String {
+    phone_in(rng, NANP)
+}
+
+// a NANP number like phone() , or 9 digits after any other country code : "+44207946095"
+pub fn phone_in<R: Rng + ?Sized>(rng: &mut R, country_code: u16) -> String {
+    if country_code != NANP {
+        return format!("+{}{}{:08}", country_code, rng.gen_range(1..10), rng.gen_range(0..100_000_000));
+    }
+    format!(
+        "+1{}{:02}{}{:02}{:04}",
+        rng.gen_range(2..10),
+        rng.gen_range(0..100),
+        rng.gen_range(2..10),
+        rng.gen_range(0..100),
+        rng.gen_range(0..10000)
+    )
+}
+
+// "184 Bergen Court, Gorham, American Samoa, 8722"
+pub fn address<R: Rng + ?Sized>(rng: &mut R) -> String {
+    format!(
+        "{} {}, {}, {}, {}",
+        rng.gen_range(100..1000),
+        pick(rng, STREETS),
+        pick(rng, CITIES),
+        pick(rng, REGIONS),
+        rng.gen_range(1000..10000)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Address, PhoneNumber};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_fake_values_are_valid() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            assert!(PhoneNumber::parse(&phone(&mut rng), None).is_ok());
+            assert_eq!(PhoneNumber::parse(&phone_in(&mut rng, 353), None).map(|p| p.country_code), Ok(353));
+            assert!(address(&mut rng).parse::<Address>().is_ok());
+        }
+        assert_eq!(email("Mabel Haley", "ENOMEN"), "mabelhaley@enomen.com");
+    }
+}
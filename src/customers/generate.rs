@@ -0,0 +1,115 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::customers::fake;
+use crate::model::{Customer, ObjectId, NANP};
+
+/*
+    made up customers for load tests and demos.
+
+    the same seed always gives the same customers , so a test can generate its data
+    instead of keeping a fixture file. the generator is an iterator that never ends ,
+    take() as many customers as needed , one at a time.
+*/
+
+pub const EYE_COLORS: &[&str] = &["brown", "blue", "green"];
+
+// roughly half and half , with a few "other"
+const GENDERS: &[(&str, u32)] = &[("female", 48), ("male", 48), ("other", 4)];
+
+// ids start at this time (2022-08-29) , like the ids of the sample customers
+const FIRST_TIMESTAMP: u32 = 0x630c_2272;
+
+pub struct Generator {
+    rng: StdRng,
+    country_code: u16,
+    timestamp: u32,
+    counter: u32,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let counter = rng.gen_range(0..0x100_0000);
+        Generator { rng, country_code: NANP, timestamp: FIRST_TIMESTAMP, counter }
+    }
+
+    // the country of the phone numbers , NANP (+1) by default , a known country code (see model::phone)
+    pub fn with_country(mut self, country_code: u16) -> Generator {
+        self.country_code = country_code;
+        self
+    }
+
+    // the timestamp moves forward a few seconds per customer , so ids sort in creation order
+    fn id(&mut self) -> ObjectId {
+        self.timestamp += self.rng.gen_range(0..5);
+        self.counter = (self.counter + 1) & 0xff_ffff;
+        ObjectId::from_parts(self.timestamp, self.rng.gen(), self.counter)
+    }
+
+    // between 18 and 84 , most customers in their 40s and 50s
+    fn age(&mut self) -> u32 {
+        18 + (0..3).map(|_| self.rng.gen_range(0..=22)).sum::<u32>()
+    }
+
+    pub fn customer(&mut self) -> Customer {
+        let customerid = self.id();
+        let age = self.age();
+        let gender = GENDERS.choose_weighted(&mut self.rng, |g| g.1).map(|g| g.0).unwrap_or("other");
+        let eyecolor = EYE_COLORS.choose(&mut self.rng).copied().unwrap_or("brown");
+        let name = fake::name(&mut self.rng);
+        let company = fake::company(&mut self.rng);
+        Customer {
+            customerid,
+            age: Some(age),
+            eyecolor: eyecolor.to_string(),
+            email: fake::email(&name, company),
+            name,
+            gender: gender.to_string(),
+            company: company.to_string(),
+            phone: fake::phone_in(&mut self.rng, self.country_code),
+            address: fake::address(&mut self.rng),
+            extra: Default::default(),
+        }
+    }
+}
+
+impl Iterator for Generator {
+    type Item = Customer;
+
+    fn next(&mut self) -> Option<Customer> {
+        Some(self.customer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::customers::Validator;
+
+    #[test]
+    fn test_same_seed_same_customers() {
+        let a: Vec<Customer> = Generator::new(42).take(20).collect();
+        let b: Vec<Customer> = Generator::new(42).take(20).collect();
+        let c: Vec<Customer> = Generator::new(43).take(20).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_generated_customers_are_valid() {
+        let customers: Vec<Customer> = Generator::new(7).take(500).collect();
+
+        let reports = Validator::new().validate_all(&customers);
+        assert!(reports.iter().all(|r| r.is_valid()), "{:?}", reports.iter().find(|r| !r.is_valid()));
+
+        // ids are unique , and their timestamps never go back
+        let ids: std::collections::HashSet<ObjectId> = customers.iter().map(|c| c.customerid).collect();
+        assert_eq!(ids.len(), customers.len());
+        assert!(customers.windows(2).all(|w| w[0].customerid.timestamp() <= w[1].customerid.timestamp()));
+        let c = &customers[0];
+        assert!(c.email.starts_with(&c.name.replace(' ', "").to_lowercase()));
+        assert!(customers.iter().all(|c| (18..=84).contains(&c.age.unwrap())));
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::model::{RecordField, SCHEMA_VERSION};
+
+use super::validate::{EMAIL_PATTERN, EYECOLOR_PATTERN, GENDERS, MAX_AGE, MIN_AGE, NAME_PATTERN, PHONE_PATTERN};
+
+/*
+    JSON Schema (draft 2020-12) of a customer record , to hand to the teams that send us customers ,
+    and a checker of JSON documents against a schema , ours or any other one.
+
+    customer_schema() is built from model::RecordField , the table the deserializer reads records with ,
+    and the rules of customers::Validator : every field is a property under its name and its aliases ,
+    the required ones must be there under one of them , a nullable one may be null.
+
+    the checker knows these keywords , the others (title , description , format ...) are ignored :
+
+        type , enum , const ,
+        properties , required , additionalProperties , items , minItems , maxItems ,
+        minimum , maximum , exclusiveMinimum , exclusiveMaximum , minLength , maxLength , pattern ,
+        allOf , anyOf , oneOf , not , $ref (only to "#..." , inside the same schema)
+
+    every violation comes with the JSON pointer (RFC 6901) of the value that broke it.
+*/
+
+pub const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/*
+    more $ref hops than this without going down into the document , and a $ref is taken to point back at itself.
+    a $ref that goes down (e.g. "items": { "$ref": "#" }) is stopped by the depth of the document ,
+    so going down into an item or a property starts the count again.
+*/
+const MAX_REF_HOPS: usize = 64;
+
+fn field_schema(field: RecordField) -> Value {
+    let schema = match field {
+        RecordField::CustomerId => json!({
+            "type": "string",
+            "description": "an ObjectId , 24 hex digits",
+            "pattern": "^[0-9a-fA-F]{24}$",
+        }),
+        RecordField::Age => json!({ "type": "integer", "minimum": MIN_AGE, "maximum": MAX_AGE }),
+        RecordField::EyeColor => json!({ "type": "string", "pattern": EYECOLOR_PATTERN }),
+        RecordField::Name => json!({ "type": "string", "pattern": NAME_PATTERN }),
+        RecordField::Gender => json!({ "type": "string", "enum": GENDERS }),
+        RecordField::Company => json!({ "type": "string", "minLength": 1 }),
+        RecordField::Email => json!({ "type": "string", "format": "email", "pattern": EMAIL_PATTERN }),
+        RecordField::Phone => json!({ "type": "string", "description": "a phone number in E.164 , e.g. +18805162365", "pattern": PHONE_PATTERN }),
+        RecordField::Address => json!({
+            "type": "string",
+            "description": "street number and street , city , region , postal code",
+            "minLength": 1,
+        }),
+    };
+    if !field.nullable() {
+        return schema;
+    }
+    // null is no value at all , the bounds only apply to the others
+    let mut schema = schema;
+    if let Some(t) = schema.get_mut("type") {
+        *t = json!([t.clone(), "null"]);
+    }
+    schema
+}
+
+// one customer record , as written by rapp1
+pub fn customer_schema() -> Value {
+    let mut properties = Map::new();
+    properties.insert(String::from("version"), json!({ "type": "integer", "minimum": 1, "maximum": SCHEMA_VERSION }));
+    let mut required = Vec::new();
+    let mut required_keys = Vec::new();
+    for field in RecordField::ALL {
+        let keys = std::iter::once(field.name()).chain(field.aliases().iter().copied());
+        for key in keys.clone() {
+            properties.insert(key.to_string(), field_schema(field));
+        }
+        match field.aliases() {
+            _ if !field.required() => {}
+            [] => required.push(field.name()),
+            _ => required_keys.push(json!({ "anyOf": keys.map(|key| json!({ "required": [key] })).collect::<Vec<_>>() })),
+        }
+    }
+    json!({
+        "$schema": DRAFT,
+        "title": "Customer",
+        "description": format!("a customer record , version {} ; keys that are not listed are kept as they are", SCHEMA_VERSION),
+        "type": "object",
+        "required": required,
+        "allOf": required_keys,
+        "properties": properties,
+        "additionalProperties": true,
+    })
+}
+
+// a JSON array file of customer records
+pub fn file_schema() -> Value {
+    let mut item = customer_schema();
+    if let Some(item) = item.as_object_mut() {
+        item.remove("$schema");
+    }
+    json!({
+        "$schema": DRAFT,
+        "title": "Customers",
+        "type": "array",
+        "items": item,
+    })
+}
+
+// one broken rule , at the value found at pointer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the pointer of the whole document is "" , hard to see on its own
+        let pointer = if self.pointer.is_empty() { "(document)" } else { &self.pointer };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+// "~" and "/" inside a key are written "~0" and "~1"
+fn push_pointer(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, token.replace('~', "~0").replace('/', "~1"))
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if is_integer(n) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// 3.0 is an integer as well
+fn is_integer(n: &serde_json::Number) -> bool {
+    n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+}
+
+fn has_type(v: &Value, name: &str) -> bool {
+    let actual = type_name(v);
+    actual == name || (name == "number" && actual == "integer")
+}
+
+/*
+    a schema ready to check documents with , the patterns are compiled once here.
+    a schema that is not valid itself (a pattern that does not compile , a $ref that
+    points nowhere) shows up as violations of the documents it checks.
+*/
+pub struct Schema {
+    root: Value,
+    patterns: HashMap<String, Result<Regex, String>>,
+}
+
+impl Schema {
+    pub fn new(root: Value) -> Schema {
+        let mut patterns = HashMap::new();
+        collect_patterns(&root, &mut patterns);
+        Schema { root, patterns }
+    }
+
+    pub fn customer() -> Schema {
+        Schema::new(customer_schema())
+    }
+
+    pub fn schema(&self) -> &Value {
+        &self.root
+    }
+
+    // every violation of the document , an empty list when it is valid
+    pub fn validate(&self, instance: &Value) -> Vec<Violation> {
+        let mut out = Vec::new();
+        self.check(&self.root, instance, "", 0, &mut out);
+        out
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validate(instance).is_empty()
+    }
+
+    // depth is the number of $ref hops since the last step down into the document
+    fn check(&self, schema: &Value, v: &Value, pointer: &str, depth: usize, out: &mut Vec<Violation>) {
+        let mut fail = |message: String| out.push(Violation { pointer: pointer.to_string(), message });
+        let s = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => return fail(String::from("no value is allowed here")),
+            Value::Object(s) => s,
+            _ => return fail(String::from("the schema is not an object or a boolean")),
+        };
+        if depth > MAX_REF_HOPS {
+            return fail(String::from("the schema is nested too deep , is there a $ref loop ?"));
+        }
+
+        if let Some(r) = s.get("$ref").and_then(Value::as_str) {
+            match r.strip_prefix('#').and_then(|p| self.root.pointer(p)) {
+                Some(target) => self.check(target, v, pointer, depth + 1, out),
+                None => out.push(Violation { pointer: pointer.to_string(), message: format!("$ref '{}' is not found in the schema", r) }),
+            }
+        }
+        let mut fail = |message: String| out.push(Violation { pointer: pointer.to_string(), message });
+
+        match s.get("type") {
+            Some(Value::String(t)) if !has_type(v, t) => fail(format!("expected {} , found {}", t, type_name(v))),
+            Some(Value::Array(ts)) if !ts.iter().filter_map(Value::as_str).any(|t| has_type(v, t)) => {
+                let names: Vec<&str> = ts.iter().filter_map(Value::as_str).collect();
+                fail(format!("expected one of {} , found {}", names.join(", "), type_name(v)))
+            }
+            _ => {}
+        }
+        if let Some(Value::Array(allowed)) = s.get("enum") {
+            if !allowed.contains(v) {
+                let names: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                fail(format!("{} is not one of {}", v, names.join(", ")));
+            }
+        }
+        if let Some(c) = s.get("const") {
+            if c != v {
+                fail(format!("{} is not {}", v, c));
+            }
+        }
+
+        match v {
+            Value::Number(n) => {
+                let x = n.as_f64().unwrap_or(0.0);
+                let bound = |key: &str| s.get(key).and_then(Value::as_f64);
+                if let Some(min) = bound("minimum").filter(|&min| x < min) {
+                    fail(format!("{} is less than the minimum of {}", n, min));
+                }
+                if let Some(max) = bound("maximum").filter(|&max| x > max) {
+                    fail(format!("{} is more than the maximum of {}", n, max));
+                }
+                if let Some(min) = bound("exclusiveMinimum").filter(|&min| x <= min) {
+                    fail(format!("{} is not more than {}", n, min));
+                }
+                if let Some(max) = bound("exclusiveMaximum").filter(|&max| x >= max) {
+                    fail(format!("{} is not less than {}", n, max));
+                }
+            }
+            Value::String(text) => {
+                // lengths are in characters , not bytes
+                let len = text.chars().count() as u64;
+                if let Some(min) = s.get("minLength").and_then(Value::as_u64).filter(|&min| len < min) {
+                    fail(format!("'{}' is shorter than {} character(s)", text, min));
+                }
+                if let Some(max) = s.get("maxLength").and_then(Value::as_u64).filter(|&max| len > max) {
+                    fail(format!("'{}' is longer than {} character(s)", text, max));
+                }
+                if let Some(pattern) = s.get("pattern").and_then(Value::as_str) {
+                    match self.patterns.get(pattern) {
+                        Some(Ok(re)) if !re.is_match(text) => fail(format!("'{}' does not match {}", text, pattern)),
+                        Some(Err(e)) => fail(format!("pattern {} does not compile : {}", pattern, e)),
+                        _ => {}
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let len = items.len() as u64;
+                if let Some(min) = s.get("minItems").and_then(Value::as_u64).filter(|&min| len < min) {
+                    fail(format!("{} item(s) , at least {} are needed", len, min));
+                }
+                if let Some(max) = s.get("maxItems").and_then(Value::as_u64).filter(|&max| len > max) {
+                    fail(format!("{} item(s) , at most {} are allowed", len, max));
+                }
+                if let Some(item) = s.get("items") {
+                    for (i, x) in items.iter().enumerate() {
+                        self.check(item, x, &push_pointer(pointer, &i.to_string()), 0, out);
+                    }
+                }
+            }
+            Value::Object(o) => {
+                if let Some(Value::Array(required)) = s.get("required") {
+                    for key in required.iter().filter_map(Value::as_str) {
+                        if !o.contains_key(key) {
+                            fail(format!("'{}' is required", key));
+                        }
+                    }
+                }
+                let properties = s.get("properties").and_then(Value::as_object);
+                for (key, x) in o {
+                    let at = push_pointer(pointer, key);
+                    match properties.and_then(|p| p.get(key)) {
+                        Some(property) => self.check(property, x, &at, 0, out),
+                        None => match s.get("additionalProperties") {
+                            Some(Value::Bool(false)) => out.push(Violation { pointer: at, message: String::from("is not an allowed key") }),
+                            Some(additional) => self.check(additional, x, &at, 0, out),
+                            None => {}
+                        },
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // the combinations , with the violations of the parts only when all of them failed
+        if let Some(Value::Array(all)) = s.get("allOf") {
+            for part in all {
+                self.check(part, v, pointer, depth, out);
+            }
+        }
+        let failures = |parts: &Vec<Value>| parts.iter().map(|part| self.sub(part, v, pointer, depth)).collect::<Vec<_>>();
+        if let Some(Value::Array(any)) = s.get("anyOf") {
+            let results = failures(any);
+            if !results.iter().any(Vec::is_empty) {
+                out.push(Violation { pointer: pointer.to_string(), message: format!("matches none of the {} anyOf schemas", any.len()) });
+                out.extend(results.into_iter().flatten());
+            }
+        }
+        if let Some(Value::Array(one)) = s.get("oneOf") {
+            let matched = failures(one).iter().filter(|r| r.is_empty()).count();
+            if matched != 1 {
+                out.push(Violation { pointer: pointer.to_string(), message: format!("matches {} of the oneOf schemas , not exactly 1", matched) });
+            }
+        }
+        if let Some(not) = s.get("not") {
+            if self.sub(not, v, pointer, depth).is_empty() {
+                out.push(Violation { pointer: pointer.to_string(), message: String::from("matches a schema it must not match") });
+            }
+        }
+    }
+
+    fn sub(&self, schema: &Value, v: &Value, pointer: &str, depth: usize) -> Vec<Violation> {
+        let mut out = Vec::new();
+        self.check(schema, v, pointer, depth, &mut out);
+        out
+    }
+}
+
+fn collect_patterns(schema: &Value, patterns: &mut HashMap<String, Result<Regex, String>>) {
+    match schema {
+        Value::Object(o) => {
+            if let Some(Value::String(p)) = o.get("pattern") {
+                patterns.entry(p.clone()).or_insert_with(|| Regex::new(p).map_err(|e| e.to_string()));
+            }
+            o.values().for_each(|v| collect_patterns(v, patterns));
+        }
+        Value::Array(a) => a.iter().for_each(|v| collect_patterns(v, patterns)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::customers::generate::Generator;
+    use crate::model::Customer;
+
+    fn pointers(violations: &[Violation]) -> Vec<&str> {
+        violations.iter().map(|v| v.pointer.as_str()).collect()
+    }
+
+    #[test]
+    fn test_customer_schema() {
+        let schema = customer_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        let aliases: usize = RecordField::ALL.iter().map(|f| f.aliases().len()).sum();
+        assert_eq!(properties.len(), Customer::FIELDS.len() + aliases + 1);
+        assert_eq!(schema["properties"]["age"]["maximum"], MAX_AGE);
+
+        // what rapp1 writes is valid , one by one and as a file
+        let customers: Vec<Customer> = Generator::new(7).take(20).collect();
+        let schema = Schema::customer();
+        for c in &customers {
+            assert_eq!(schema.validate(&serde_json::to_value(c).unwrap()), vec![], "{:?}", c);
+        }
+        assert!(Schema::new(file_schema()).is_valid(&serde_json::to_value(&customers).unwrap()));
+    }
+
+    // what the deserializer reads , the schema takes , and the other way around
+    #[test]
+    fn test_schema_agrees_with_the_deserializer() {
+        let schema = Schema::customer();
+        let docs = [
+            json!({ "customerid": "630c2272eabd3d30fe44d139", "name": "Mabel Haley", "age": null }),
+            json!({ "customer_id": "630c2272eabd3d30fe44d139", "name": "Mabel Haley", "eyeColor": "blue" }),
+            json!({ "customerId": "630c2272eabd3d30fe44d139", "name": "Mabel Haley", "phoneNumber": "+18805162365" }),
+            json!({ "name": "Mabel Haley" }),
+            json!({ "customerid": "630c2272eabd3d30fe44d139" }),
+            json!({ "customerid": "630c2272eabd3d30fe44d139", "name": "Mabel Haley", "gender": null }),
+        ];
+        for doc in &docs {
+            let read = serde_json::from_value::<Customer>(doc.clone()).is_ok();
+            assert_eq!(schema.is_valid(doc), read, "{}", doc);
+        }
+        assert_eq!(schema.validate(&docs[1]), vec![]);
+        // an alias is checked like the field
+        let bad = json!({ "customer_id": "xyz", "name": "Mabel Haley" });
+        assert_eq!(pointers(&schema.validate(&bad)), ["/customer_id"]);
+        assert_eq!(schema.validate(&docs[3])[0].message, "matches none of the 3 anyOf schemas");
+    }
+
+    #[test]
+    fn test_violations_have_pointers() {
+        let schema = Schema::new(file_schema());
+        let doc = json!([
+            { "customerid": "630c2272eabd3d30fe44d139", "name": "Mabel Haley", "age": 28 },
+            { "customerid": "xyz", "age": 300, "gender": "robot", "email": "nope", "loyalty": { "tier": "gold" } },
+            "not a customer",
+        ]);
+        let violations = schema.validate(&doc);
+        // the keys of an object are checked in sorted order
+        assert_eq!(pointers(&violations), ["/1", "/1/age", "/1/customerid", "/1/email", "/1/gender", "/2"]);
+        assert_eq!(violations[0].message, "'name' is required");
+        assert_eq!(violations[5].to_string(), "/2: expected object , found string");
+    }
+
+    #[test]
+    fn test_keywords() {
+        let schema = Schema::new(json!({
+            "$defs": { "tag": { "type": "string", "maxLength": 3 } },
+            "type": "object",
+            "properties": {
+                "tags": { "type": "array", "items": { "$ref": "#/$defs/tag" }, "maxItems": 2 },
+                "a/b": { "anyOf": [{ "type": "integer" }, { "const": "none" }] },
+                "n": { "type": ["number", "null"], "exclusiveMinimum": 0 },
+            },
+            "additionalProperties": false,
+        }));
+        assert!(schema.is_valid(&json!({ "tags": ["a", "bc"], "a/b": 3.0, "n": null })));
+
+        let violations = schema.validate(&json!({ "tags": ["a", "long", "x"], "a/b": "x", "n": 0, "z": 1 }));
+        assert_eq!(pointers(&violations), ["/a~1b", "/a~1b", "/a~1b", "/n", "/tags", "/tags/1", "/z"]);
+
+        // a document deeper than MAX_REF_HOPS is fine , a $ref that goes nowhere is not
+        let nested = Schema::new(json!({ "type": ["array", "integer"], "items": { "$ref": "#" } }));
+        let mut doc = json!(1);
+        for _ in 0..100 {
+            doc = json!([doc]);
+        }
+        assert!(nested.is_valid(&doc));
+        let looping = Schema::new(json!({ "$defs": { "a": { "$ref": "#/$defs/b" }, "b": { "allOf": [{ "$ref": "#/$defs/a" }] } }, "$ref": "#/$defs/a" }));
+        assert_eq!(looping.validate(&json!(1))[0].message, "the schema is nested too deep , is there a $ref loop ?");
+
+        let broken = Schema::new(json!({ "pattern": "(", "items": { "$ref": "#/nope" } }));
+        assert_eq!(broken.validate(&json!("x")).len(), 1);
+        assert_eq!(broken.validate(&json!([1])).len(), 1);
+    }
+}
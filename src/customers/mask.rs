@@ -0,0 +1,330 @@
+use hmac::{Hmac, Mac};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::customers::dedup::normalize_email;
+use crate::customers::fake;
+use crate::model::{Address, Customer, PhoneNumber};
+
+/*
+    masking of the personal fields of a customer (name , email , phone , address)
+    before the data is shared. every field has its own strategy :
+
+        keep     -> left as it is
+        redact   -> replaced by "[redacted]"
+        partial  -> mostly hidden : "M**** H****" , "m***@enomen.com" , "+* (***) ***-2365"
+        hash     -> a keyed hash of the value , the same value always gives the same hash ,
+                    so customers can still be joined on the field , but without the key
+                    the hash can not be checked against guessed values
+        fake     -> a made up value of the same kind , also picked with the keyed hash ,
+                    so the same real value always gets the same fake one
+
+    the extra keys of a record (see model::Customer::extra) can hold anything , "ssn" , "mobile" ,
+    "first_name" ... so every value in them is redacted , unless its key was allowed with keep_extra()
+*/
+
+pub const REDACTED: &str = "[redacted]";
+
+// hashed emails keep the shape of an email , at a domain that can not receive mail
+pub const HASH_DOMAIN: &str = "masked.invalid";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Keep,
+    Redact,
+    Partial,
+    Hash,
+    Fake,
+}
+
+impl Strategy {
+    pub fn parse(s: &str) -> Option<Strategy> {
+        match s {
+            "keep" => Some(Strategy::Keep),
+            "redact" => Some(Strategy::Redact),
+            "partial" => Some(Strategy::Partial),
+            "hash" => Some(Strategy::Hash),
+            "fake" => Some(Strategy::Fake),
+            _ => None,
+        }
+    }
+
+    // hash and fake need a key
+    pub fn is_keyed(self) -> bool {
+        matches!(self, Strategy::Hash | Strategy::Fake)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Email,
+    Phone,
+    Address,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [Field::Name, Field::Email, Field::Phone, Field::Address];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Email => "email",
+            Field::Phone => "phone",
+            Field::Address => "address",
+        }
+    }
+}
+
+pub struct Masker {
+    key: Vec<u8>,
+    // used to normalize phone numbers before they are hashed
+    default_country: Option<u16>,
+    name: Strategy,
+    email: Strategy,
+    phone: Strategy,
+    address: Strategy,
+    // the extra keys that are written as they are
+    keep_extra: Vec<String>,
+}
+
+impl Masker {
+    // every field redacted , until another strategy is set with with()
+    pub fn new(key: &[u8]) -> Masker {
+        Masker {
+            key: key.to_vec(),
+            default_country: None,
+            name: Strategy::Redact,
+            email: Strategy::Redact,
+            phone: Strategy::Redact,
+            address: Strategy::Redact,
+            keep_extra: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, field: Field, strategy: Strategy) -> Masker {
+        *self.strategy_mut(field) = strategy;
+        self
+    }
+
+    // an extra key whose value is not redacted
+    pub fn keep_extra(mut self, key: &str) -> Masker {
+        self.keep_extra.push(key.to_string());
+        self
+    }
+
+    pub fn with_default_country(mut self, cc: u16) -> Masker {
+        self.default_country = Some(cc);
+        self
+    }
+
+    pub fn strategy(&self, field: Field) -> Strategy {
+        match field {
+            Field::Name => self.name,
+            Field::Email => self.email,
+            Field::Phone => self.phone,
+            Field::Address => self.address,
+        }
+    }
+
+    fn strategy_mut(&mut self, field: Field) -> &mut Strategy {
+        match field {
+            Field::Name => &mut self.name,
+            Field::Email => &mut self.email,
+            Field::Phone => &mut self.phone,
+            Field::Address => &mut self.address,
+        }
+    }
+
+    pub fn mask(&self, c: &mut Customer) {
+        c.name = self.mask_value(Field::Name, &c.name);
+        c.email = self.mask_value(Field::Email, &c.email);
+        c.phone = self.mask_value(Field::Phone, &c.phone);
+        c.address = self.mask_value(Field::Address, &c.address);
+        for (key, value) in c.extra.iter_mut() {
+            if !value.is_null() && !self.keep_extra.contains(key) {
+                *value = Value::String(REDACTED.to_string());
+            }
+        }
+    }
+
+    pub fn mask_value(&self, field: Field, value: &str) -> String {
+        match self.strategy(field) {
+            Strategy::Keep => value.to_string(),
+            Strategy::Redact => REDACTED.to_string(),
+            Strategy::Partial => partial(field, value),
+            Strategy::Hash => {
+                let hex: String = self.digest(field, value)[..16].iter().map(|b| format!("{:02x}", b)).collect();
+                match field {
+                    Field::Email => format!("{}@{}", hex, HASH_DOMAIN),
+                    _ => hex,
+                }
+            }
+            Strategy::Fake => {
+                let mut rng = StdRng::from_seed(self.digest(field, value));
+                match field {
+                    Field::Name => fake::name(&mut rng),
+                    Field::Email => fake::email(&fake::name(&mut rng), "example"),
+                    Field::Phone => fake::phone(&mut rng),
+                    Field::Address => fake::address(&mut rng),
+                }
+            }
+        }
+    }
+
+    /*
+        HMAC-SHA256 of the field name and the normalized value , so "Mabel.Haley@Enomen.com"
+        and "mabel.haley@enomen.com" , or "+1 (880) 516-2365" and "+18805162365" hash the same
+    */
+    fn digest(&self, field: Field, value: &str) -> [u8; 32] {
+        let normalized = match field {
+            Field::Email => normalize_email(value),
+            Field::Phone => match PhoneNumber::parse(value, self.default_country) {
+                Ok(p) => p.e164(),
+                Err(_) => value.chars().filter(char::is_ascii_digit).collect(),
+            },
+            Field::Name | Field::Address => words(value),
+        };
+
+        // an HMAC takes a key of any length , new_from_slice can not fail
+        let mut mac = <Hmac<Sha256>>::new_from_slice(&self.key).expect("hmac accepts any key length");
+        mac.update(field.name().as_bytes());
+        mac.update(&[0]);
+        mac.update(normalized.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+}
+
+// lower case words , joined by one space
+fn words(s: &str) -> String {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// every character but the first one of each word
+fn stars_after_first(s: &str) -> String {
+    s.split(' ')
+        .map(|w| w.chars().enumerate().map(|(i, c)| if i == 0 { c } else { '*' }).collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn partial(field: Field, value: &str) -> String {
+    match field {
+        Field::Name => stars_after_first(value),
+        // the first letter and the domain , the number of stars does not give away the length
+        Field::Email => match value.split_once('@') {
+            Some((local, domain)) => format!("{}***@{}", local.chars().next().unwrap_or('*'), domain),
+            None => String::from("***"),
+        },
+        // only the last 4 digits
+        Field::Phone => {
+            let digits = value.chars().filter(char::is_ascii_digit).count();
+            let mut seen = 0;
+            value
+                .chars()
+                .map(|c| {
+                    if !c.is_ascii_digit() {
+                        return c;
+                    }
+                    seen += 1;
+                    if seen + 4 > digits { c } else { '*' }
+                })
+                .collect()
+        }
+        // the city and the region
+        Field::Address => match value.parse::<Address>() {
+            Ok(a) => Address {
+                street_number: String::from("***"),
+                street: String::from("***"),
+                postal_code: String::from("****"),
+                ..a
+            }
+            .to_string(),
+            Err(_) => REDACTED.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mabel() -> Customer {
+        Customer::sample("630c2272eabd3d30fe44d139", "Mabel Haley", 28)
+    }
+
+    #[test]
+    fn test_redact_and_partial() {
+        let mut c = mabel();
+        Masker::new(b"").mask(&mut c);
+        assert_eq!((c.name.as_str(), c.address.as_str()), (REDACTED, REDACTED));
+        assert_eq!((c.customerid.to_string().as_str(), c.company.as_str()), ("630c2272eabd3d30fe44d139", "ENOMEN"));
+
+        let mut masker = Masker::new(b"");
+        for field in Field::ALL {
+            masker = masker.with(field, Strategy::Partial);
+        }
+        // partial keeps the layout of the number
+        let mut c = Customer { phone: String::from("+1 (880) 516-2365"), ..mabel() };
+        masker.mask(&mut c);
+        assert_eq!(c.name, "M**** H****");
+        assert_eq!(c.email, "m***@enomen.com");
+        assert_eq!(c.phone, "+* (***) ***-2365");
+        assert_eq!(c.address, "*** ***, Gorham, American Samoa, ****");
+    }
+
+    #[test]
+    fn test_extra_keys_are_redacted() {
+        let mut c = mabel();
+        for (key, value) in [("first_name", "Mabel"), ("last_name", "Haley"), ("ssn", "078-05-1120"), ("mobile", "+1 880 516 2365"), ("team", "blue")] {
+            c.extra.insert(key.to_string(), Value::from(value));
+        }
+        c.extra.insert(String::from("pin"), Value::from(1234));
+        c.extra.insert(String::from("notes"), Value::Null);
+
+        let masker = Masker::new(b"").with(Field::Name, Strategy::Keep).keep_extra("team");
+        masker.mask(&mut c);
+        for key in ["first_name", "last_name", "ssn", "mobile", "pin"] {
+            assert_eq!(c.extra[key], REDACTED, "{}", key);
+        }
+        assert_eq!((&c.extra["team"], &c.extra["notes"]), (&Value::from("blue"), &Value::Null));
+        assert_eq!(c.name, "Mabel Haley");
+    }
+
+    #[test]
+    fn test_hash_keeps_joins() {
+        let masker = Masker::new(b"secret").with(Field::Email, Strategy::Hash).with(Field::Phone, Strategy::Hash);
+
+        let a = masker.mask_value(Field::Email, "mabelhaley@enomen.com");
+        assert_eq!(a, masker.mask_value(Field::Email, " MabelHaley@Enomen.com"));
+        assert!(a.ends_with("@masked.invalid"));
+        assert_ne!(a, masker.mask_value(Field::Email, "whitneycarver@zillacon.com"));
+        assert_eq!(masker.mask_value(Field::Phone, "+1 (880) 516-2365"), masker.mask_value(Field::Phone, "+18805162365"));
+
+        // another key , another hash
+        let other = Masker::new(b"other").with(Field::Email, Strategy::Hash);
+        assert_ne!(a, other.mask_value(Field::Email, "mabelhaley@enomen.com"));
+    }
+
+    #[test]
+    fn test_fake() {
+        let mut masker = Masker::new(b"secret");
+        for field in Field::ALL {
+            masker = masker.with(field, Strategy::Fake);
+        }
+        let (mut a, mut b) = (mabel(), mabel());
+        masker.mask(&mut a);
+        masker.mask(&mut b);
+        assert_eq!(a, b);
+        assert_ne!(a.name, "Mabel Haley");
+        assert!(a.email.ends_with("@example.com"));
+        assert!(PhoneNumber::parse(&a.phone, None).is_ok());
+        assert!(a.parsed_address().is_ok());
+    }
+}
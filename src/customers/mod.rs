@@ -0,0 +1,40 @@
+/*
+    customers -> tooling built on top of model::Customer
+
+    store    -> CustomerStore, customers loaded from a JSON array or NDJSON file
+    validate -> field validation rules , with a list of errors per record
+    csv      -> reading and writing customers as CSV
+    address  -> report of addresses that do not parse , grouping by city / region
+    phone    -> phone numbers normalized to E.164 , duplicates by phone number
+    stream   -> one record at a time reading / writing , for files too big to load
+    query    -> filter expressions , sorting and pagination
+    stats    -> age distribution , counts per gender / eyecolor / company / email domain
+    dedup    -> fuzzy duplicate detection , clusters of likely duplicates , merging
+    diff     -> added , removed and changed customers between two snapshots
+    mask     -> masking of names , emails , phones and addresses before data is shared
+    fake     -> made up names , emails , phones and addresses
+    generate -> seedable generator of made up customers
+    jsonschema -> JSON Schema of a customer record , checking documents against a schema
+    sqlite   -> customers in a SQLite database (feature "sqlite")
+*/
+
+pub mod address;
+pub mod csv;
+pub mod dedup;
+pub mod diff;
+pub mod fake;
+pub mod generate;
+pub mod jsonschema;
+pub mod mask;
+pub mod phone;
+pub mod query;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod stats;
+pub mod store;
+pub mod stream;
+pub mod validate;
+
+pub use self::csv::CsvError;
+pub use self::store::{CustomerStore, Format, StoreError};
+pub use self::validate::{FieldError, RecordReport, Validator};
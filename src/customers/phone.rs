@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use crate::model::{Customer, ObjectId, PhoneError, PhoneNumber};
+
+// a customer whose phone number could not be normalized
+#[derive(Debug, Clone)]
+pub struct BadPhone {
+    pub index: usize,
+    pub customerid: ObjectId,
+    pub phone: String,
+    pub error: PhoneError,
+}
+
+// rewrite the phone number of one customer to E.164 , it is left as it is when it does not parse
+pub fn normalize(c: &mut Customer, default_country: Option<u16>) -> Result<(), PhoneError> {
+    c.phone = PhoneNumber::parse(&c.phone, default_country)?.e164();
+    Ok(())
+}
+
+/*
+    rewrite every phone number to E.164 ("+18805162365").
+    numbers that do not parse are left as they are, and returned.
+*/
+pub fn normalize_all(customers: &mut [Customer], default_country: Option<u16>) -> Vec<BadPhone> {
+    let mut bad = Vec::new();
+    for (index, c) in customers.iter_mut().enumerate() {
+        match normalize(c, default_country) {
+            Ok(_) => {}
+            Err(error) => bad.push(BadPhone {
+                index,
+                customerid: c.customerid,
+                phone: c.phone.clone(),
+                error,
+            }),
+        }
+    }
+    bad
+}
+
+/*
+    customers that share a phone number once it is normalized ,
+    keyed by the E.164 number ; only numbers used by more than one customer are returned
+*/
+pub fn duplicates(customers: &[Customer], default_country: Option<u16>) -> BTreeMap<String, Vec<&Customer>> {
+    let mut by_number: BTreeMap<String, Vec<&Customer>> = BTreeMap::new();
+    for c in customers {
+        if let Ok(p) = PhoneNumber::parse(&c.phone, default_country) {
+            by_number.entry(p.e164()).or_default().push(c);
+        }
+    }
+    by_number.retain(|_, group| group.len() > 1);
+    by_number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str, phone: &str) -> Customer {
+        Customer {
+            phone: phone.to_string(),
+            ..Customer::sample(id, "Mabel Haley", 28)
+        }
+    }
+
+    #[test]
+    fn test_normalize_and_duplicates() {
+        let mut customers = vec![
+            customer("a", "+1 (880) 516-2365"),
+            customer("b", "880.516.2365"),
+            customer("c", "not a phone"),
+            customer("d", "+1 (904) 443-2818"),
+        ];
+
+        let dups = duplicates(&customers, Some(1));
+        assert_eq!(dups.len(), 1);
+        assert_eq!(dups["+18805162365"].len(), 2);
+
+        let bad = normalize_all(&mut customers, Some(1));
+        assert_eq!(bad.len(), 1);
+        assert_eq!(bad[0].customerid, oid("c"));
+        assert_eq!(customers[1].phone, "+18805162365");
+        assert_eq!(customers[2].phone, "not a phone");
+    }
+}
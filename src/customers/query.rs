@@ -0,0 +1,548 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::model::{Customer, ObjectId};
+
+/*
+    A small query language over the Customer fields :
+
+        age > 30 and company = "ENOMEN"
+        (gender = female or age <= 25) and not email ~ "@enomen.com"
+        city = Gorham
+
+    operators   : =  !=  >  >=  <  <=  ~ (contains , ignoring case)
+    combined by : and , or , not , ( )   ('not' and ( ) nest at most MAX_DEPTH deep)
+    values      : numbers , "quoted" or 'quoted' strings , or bare words
+
+    the fields are the ones of Customer , plus city , region and postal_code
+    which come from the parsed address (see model::Address).
+    age is compared as a whole number ; a customer without an age matches no = > >= < <= on age ,
+    but does match != (it is 'not age = 30') , and sorts before every age ,
+    customerid as an ObjectId (so in creation order) , everything else as text ignoring case ,
+    even when it looks like a number : postal_code = 8722 does not match "08722".
+*/
+
+pub const ADDRESS_FIELDS: &[&str] = &["city", "region", "postal_code"];
+
+// how deep 'not' and ( ) can nest , the parser recurses for each level and the stack is not endless
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    // character position in the query , where the problem was found
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at position {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, QueryError> {
+    Err(QueryError { position, message: message.into() })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Compare { field: String, op: Op, value: String },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+// a Customer field or one of the address fields
+pub fn is_field(name: &str) -> bool {
+    Customer::FIELDS.contains(&name) || ADDRESS_FIELDS.contains(&name)
+}
+
+// the value of a field , the address fields are empty when the address does not parse
+pub fn field_value(c: &Customer, field: &str) -> String {
+    match field {
+        "city" | "region" | "postal_code" => match c.parsed_address() {
+            Ok(a) => match field {
+                "city" => a.city,
+                "region" => a.region,
+                _ => a.postal_code,
+            },
+            Err(_) => String::new(),
+        },
+        _ => c.field(field).unwrap_or_default(),
+    }
+}
+
+// an age , None for "" (a customer without an age)
+fn age(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+// compare two values of a field , the way the module comment says ; None when there is no age to compare
+fn compare_values(field: &str, a: &str, b: &str) -> Option<Ordering> {
+    match field {
+        "age" => Some(age(a)?.cmp(&age(b)?)),
+        "customerid" => match (a.parse::<ObjectId>(), b.parse::<ObjectId>()) {
+            (Ok(x), Ok(y)) => Some(x.cmp(&y)),
+            _ => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+        },
+        _ => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+    }
+}
+
+// the order for sorting , the customers without an age come first
+fn sort_values(field: &str, a: &str, b: &str) -> Ordering {
+    match field {
+        "age" => age(a).cmp(&age(b)),
+        _ => compare_values(field, a, b).unwrap_or(Ordering::Equal),
+    }
+}
+
+impl Expr {
+    pub fn matches(&self, c: &Customer) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(c) && b.matches(c),
+            Expr::Or(a, b) => a.matches(c) || b.matches(c),
+            Expr::Not(e) => !e.matches(c),
+            Expr::Compare { field, op, value } => {
+                let actual = field_value(c, field);
+                let order = || compare_values(field, &actual, value);
+                match op {
+                    Op::Contains => actual.to_lowercase().contains(&value.to_lowercase()),
+                    Op::Eq => order() == Some(Ordering::Equal),
+                    Op::Ne => order() != Some(Ordering::Equal),
+                    Op::Gt => order() == Some(Ordering::Greater),
+                    Op::Ge => matches!(order(), Some(Ordering::Greater | Ordering::Equal)),
+                    Op::Lt => order() == Some(Ordering::Less),
+                    Op::Le => matches!(order(), Some(Ordering::Less | Ordering::Equal)),
+                }
+            }
+        }
+    }
+}
+
+// ------------- tokens -------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                tokens.push((start, Token::Open));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((start, Token::Close));
+                i += 1;
+            }
+            '"' | '\'' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return error(start, "string is not closed"),
+                        Some('\\') if i + 1 < chars.len() => {
+                            s.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&q) if q == c => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&other) => {
+                            s.push(other);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push((start, Token::Quoted(s)));
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('=', Some('=')) => (Op::Eq, 2),
+                    ('=', _) => (Op::Eq, 1),
+                    ('!', Some('=')) => (Op::Ne, 2),
+                    ('<', Some('=')) => (Op::Le, 2),
+                    ('<', _) => (Op::Lt, 1),
+                    ('>', Some('=')) => (Op::Ge, 2),
+                    ('>', _) => (Op::Gt, 1),
+                    ('~', _) => (Op::Contains, 1),
+                    _ => return error(start, format!("unexpected '{}'", c)),
+                };
+                tokens.push((start, Token::Op(op)));
+                i += len;
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&c) = chars.get(i) {
+                    if c.is_whitespace() || "()=!<>~\"'".contains(c) {
+                        break;
+                    }
+                    s.push(c);
+                    i += 1;
+                }
+                tokens.push((start, Token::Word(s)));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// ------------- parser -------------
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    // the 'not' and ( ) the parser is inside of
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map(|(p, _)| *p).unwrap_or(self.end)
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(word) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // one level deeper , an error past MAX_DEPTH ; leave() when the level is parsed
+    fn enter(&mut self, position: usize) -> Result<(), QueryError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return error(position, format!("'not' and ( ) nest more than {} deep", MAX_DEPTH));
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.not()?;
+        while self.keyword("and") {
+            left = Expr::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        if self.keyword("not") {
+            self.enter(position)?;
+            let e = self.not()?;
+            self.leave();
+            return Ok(Expr::Not(Box::new(e)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+        self.pos += 1;
+
+        let field = match token {
+            Some(Token::Open) => {
+                self.enter(position)?;
+                let e = self.or()?;
+                self.leave();
+                return match self.peek() {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(e)
+                    }
+                    _ => error(self.position(), "expected ')'"),
+                };
+            }
+            Some(Token::Word(w)) if is_field(&w) => w,
+            Some(Token::Word(w)) => return error(position, format!("unknown field '{}'", w)),
+            Some(_) => return error(position, "expected a field name"),
+            None => return error(position, "unexpected end of query , expected a field name"),
+        };
+
+        let position = self.position();
+        let op = match self.tokens.get(self.pos) {
+            Some((_, Token::Op(op))) => *op,
+            _ => return error(position, format!("expected an operator after '{}'", field)),
+        };
+        self.pos += 1;
+
+        let position = self.position();
+        let value = match self.tokens.get(self.pos) {
+            Some((_, Token::Word(w))) | Some((_, Token::Quoted(w))) => w.clone(),
+            _ => return error(position, "expected a value"),
+        };
+        self.pos += 1;
+
+        if field == "age" && op != Op::Contains && value.trim().parse::<u64>().is_err() {
+            return error(position, format!("age is compared with a whole number , not '{}'", value));
+        }
+
+        Ok(Expr::Compare { field, op, value })
+    }
+}
+
+impl FromStr for Expr {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(s)?, pos: 0, end: s.chars().count(), depth: 0 };
+        let expr = parser.or()?;
+        if parser.pos < parser.tokens.len() {
+            return error(parser.position(), "unexpected input after the end of the query");
+        }
+        Ok(expr)
+    }
+}
+
+// ------------- sort and pagination -------------
+
+// "name" sorts ascending , "-age" sorts descending ; "customerid" sorts by creation time (see model::ObjectId)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: String,
+    pub descending: bool,
+}
+
+impl FromStr for SortKey {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, descending) = match s.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (s.strip_prefix('+').unwrap_or(s), false),
+        };
+        if !is_field(field) {
+            return error(0, format!("unknown sort field '{}'", field));
+        }
+        Ok(SortKey { field: field.to_string(), descending })
+    }
+}
+
+// "company,-age" -> sort by company , then by age descending
+pub fn parse_sort(s: &str) -> Result<Vec<SortKey>, QueryError> {
+    s.split(',').filter(|k| !k.trim().is_empty()).map(|k| k.trim().parse()).collect()
+}
+
+/*
+    Query = filter + sort + offset / limit , the same three steps as the
+    server-side pagination of the DataTables notes (Rust-Data-Table.md) :
+    recordsTotal -> total , recordsFiltered -> filtered , data -> items
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub filter: Option<Expr>,
+    pub sort: Vec<SortKey>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct Page<'a> {
+    pub total: usize,
+    pub filtered: usize,
+    pub items: Vec<&'a Customer>,
+}
+
+impl Query {
+    // page numbers start at 1 ; a page past the end , however far , is empty
+    pub fn paginate(mut self, page: usize, size: usize) -> Query {
+        self.offset = page.saturating_sub(1).saturating_mul(size);
+        self.limit = Some(size);
+        self
+    }
+
+    pub fn matches(&self, c: &Customer) -> bool {
+        self.filter.as_ref().is_none_or(|e| e.matches(c))
+    }
+
+    pub fn compare(&self, a: &Customer, b: &Customer) -> Ordering {
+        for key in &self.sort {
+            let o = sort_values(&key.field, &field_value(a, &key.field), &field_value(b, &key.field));
+            let o = if key.descending { o.reverse() } else { o };
+            if o != Ordering::Equal {
+                return o;
+            }
+        }
+        Ordering::Equal
+    }
+
+    pub fn run<'a>(&self, customers: &'a [Customer]) -> Page<'a> {
+        let mut items: Vec<&Customer> = customers.iter().filter(|c| self.matches(c)).collect();
+        let filtered = items.len();
+        // a stable sort , customers that compare equal stay in file order
+        items.sort_by(|a, b| self.compare(a, b));
+        let items = items.into_iter().skip(self.offset).take(self.limit.unwrap_or(usize::MAX)).collect();
+        Page { total: customers.len(), filtered, items }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn customer(name: &str, age: u32, company: &str, address: &str) -> Customer {
+        Customer {
+            customerid: ObjectId::new(),
+            company: company.to_string(),
+            email: format!("{}@{}.com", name.to_lowercase(), company.to_lowercase()),
+            address: address.to_string(),
+            ..Customer::sample("0", name, age)
+        }
+    }
+
+    fn sample() -> Vec<Customer> {
+        vec![
+            customer("Mabel", 28, "ENOMEN", "184 Bergen Court, Gorham, American Samoa, 8722"),
+            customer("Whitney", 35, "ZILLACON", "741 Dahill Road, Tyro, Nevada, 3052"),
+            customer("Hodges", 41, "ENOMEN", "522 Hoyt Street, Bowmansville, Kentucky, 6441"),
+            customer("Lara", 9, "ENOMEN", "377 Kathleen Court, Cazadero, Georgia, 4297"),
+        ]
+    }
+
+    fn names(items: &[&Customer]) -> Vec<String> {
+        items.iter().map(|c| c.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let customers = sample();
+        let run = |q: &str| {
+            let expr: Expr = q.parse().unwrap();
+            names(&customers.iter().filter(|c| expr.matches(c)).collect::<Vec<_>>())
+        };
+
+        assert_eq!(run(r#"age > 30 and company = "ENOMEN""#), vec!["Hodges"]);
+        assert_eq!(run("age > 10"), vec!["Mabel", "Whitney", "Hodges"]);
+        assert_eq!(run("company = enomen and not (age >= 28 and age <= 41)"), vec!["Lara"]);
+        assert_eq!(run("name = Lara or region = 'Nevada'"), vec!["Whitney", "Lara"]);
+        assert_eq!(run("email ~ ZILLA"), vec!["Whitney"]);
+        assert_eq!(run("city != Gorham AND age < 40"), vec!["Whitney", "Lara"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = "age > 30 and".parse::<Expr>().unwrap_err();
+        assert_eq!(e.position, 12);
+        assert_eq!("salary > 3".parse::<Expr>().unwrap_err().message, "unknown field 'salary'");
+        assert_eq!("age > old".parse::<Expr>().unwrap_err().position, 6);
+        assert!("(age > 3".parse::<Expr>().is_err());
+        assert!("name = \"open".parse::<Expr>().is_err());
+        assert!("age > 3 age".parse::<Expr>().is_err());
+
+        // nesting is limited , so a long 'not' chain or many ( can not overflow the stack
+        let nested = |open: &str, close: &str, n: usize| format!("{}age > 1{}", open.repeat(n), close.repeat(n));
+        assert!(nested("not ", "", MAX_DEPTH).parse::<Expr>().is_ok());
+        assert!(nested("(", ")", MAX_DEPTH).parse::<Expr>().is_ok());
+        for deep in [nested("not ", "", 30_000), nested("(", ")", 30_000), nested("(not ", ")", MAX_DEPTH / 2 + 1)] {
+            let e = deep.parse::<Expr>().unwrap_err();
+            assert!(e.message.contains("nest more than 64 deep"), "{}", e);
+        }
+    }
+
+    #[test]
+    fn test_sort_and_pagination() {
+        let customers = sample();
+        let query = Query {
+            filter: Some("company = ENOMEN".parse().unwrap()),
+            sort: parse_sort("-age").unwrap(),
+            ..Query::default()
+        };
+        let page = query.clone().paginate(1, 2).run(&customers);
+        assert_eq!((page.total, page.filtered), (4, 3));
+        assert_eq!(names(&page.items), vec!["Hodges", "Mabel"]);
+        assert_eq!(names(&query.clone().paginate(2, 2).run(&customers).items), vec!["Lara"]);
+        assert!(query.clone().paginate(3, 2).run(&customers).items.is_empty());
+        assert!(query.clone().paginate(3, usize::MAX).run(&customers).items.is_empty());
+        assert_eq!(query.paginate(1, usize::MAX).run(&customers).items.len(), 3);
+
+        let by_name = Query { sort: parse_sort("name").unwrap(), ..Query::default() };
+        assert_eq!(names(&by_name.run(&customers).items), vec!["Hodges", "Lara", "Mabel", "Whitney"]);
+        assert!(parse_sort("-salary").is_err());
+    }
+
+    #[test]
+    fn test_values_by_field() {
+        // ids of digits only , or that look like 1e5 , are still ObjectIds and not numbers
+        let ids = ["000000000000000000000010", "00000000000000000000001e", "100000000000000000000000", "0000000000000000000000e5"];
+        let mut customers: Vec<Customer> = ids.iter().map(|id| Customer { customerid: id.parse().unwrap(), ..customer("x", 30, "X", "") }).collect();
+        customers[1].age = None;
+        customers[2].address = String::from("184 Bergen Court, Gorham, American Samoa, 08722");
+        customers[3].address = String::from("184 Bergen Court, Gorham, American Samoa, 8722");
+
+        let by_id = Query { sort: parse_sort("customerid").unwrap(), ..Query::default() };
+        let sorted: Vec<String> = by_id.run(&customers).items.iter().map(|c| c.customerid.to_string()).collect();
+        assert_eq!(sorted, [ids[0], ids[1], ids[3], ids[2]]);
+
+        let by_age = Query { sort: parse_sort("age").unwrap(), ..Query::default() };
+        assert_eq!(by_age.run(&customers).items[0].age, None);
+
+        let run = |q: &str| {
+            let expr: Expr = q.parse().unwrap();
+            customers.iter().filter(|c| expr.matches(c)).map(|c| c.customerid.to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(run("postal_code = 8722"), [ids[3]]);
+        assert_eq!(run("customerid = 000000000000000000000010"), [ids[0]]);
+        assert_eq!(run("age >= 30").len(), 3);
+        // no age matches no comparison , only != (the same as not age = 30)
+        for q in ["age < 30", "age <= 30", "age > 0", "age = 0"] {
+            assert!(!run(q).contains(&ids[1].to_string()), "{}", q);
+        }
+        assert_eq!(run("age != 30"), [ids[1]]);
+        assert_eq!(run("not age = 30"), [ids[1]]);
+        assert!("age > 30.5".parse::<Expr>().is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt;
+use std::path::Path;
+
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, Params, Row};
+use serde_json::{Map, Value};
+
+use crate::model::{Customer, ObjectId};
+
+use super::store::{self, StoreError};
+
+/*
+    SqliteStore -> customers in a SQLite database , for data that more than one tool writes to.
+    only built with the "sqlite" feature (cargo build --features sqlite) ; SQLite itself is
+    compiled into rapp1 , nothing has to be installed.
+
+    one table , customers , with a column per Customer field and the extra keys as a JSON object ,
+    age is NULL for a customer without one.
+    customerid is the primary key , email and company have an index ,
+    both compared ignoring case. rows keep the order they were added in (rowid).
+
+    the schema version is kept in PRAGMA user_version , open() applies the
+    MIGRATIONS the database has not seen yet , each one in its own transaction.
+*/
+
+const MIGRATIONS: &[&str] = &[
+    // version 1
+    "CREATE TABLE customers (
+        customerid TEXT PRIMARY KEY NOT NULL,
+        age        INTEGER,
+        eyecolor   TEXT NOT NULL,
+        name       TEXT NOT NULL,
+        gender     TEXT NOT NULL,
+        company    TEXT NOT NULL,
+        email      TEXT NOT NULL,
+        phone      TEXT NOT NULL,
+        address    TEXT NOT NULL,
+        extra      TEXT NOT NULL DEFAULT '{}'
+    );
+    CREATE INDEX customers_email ON customers (email COLLATE NOCASE);
+    CREATE INDEX customers_company ON customers (company COLLATE NOCASE);",
+];
+
+// the schema version open() brings every database to
+pub const DB_VERSION: u32 = MIGRATIONS.len() as u32;
+
+const COLUMNS: &str = "customerid, age, eyecolor, name, gender, company, email, phone, address, extra";
+
+#[derive(Debug)]
+pub enum DbError {
+    Sqlite(rusqlite::Error),
+    // reading or writing a customer file , and DuplicateId / NotFound
+    Store(StoreError),
+    // the database was made by a newer rapp1
+    Version(u32),
+    // a row that is not a valid customer , changed by some other tool
+    Row { customerid: String, message: String },
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Sqlite(e) => write!(f, "sqlite: {}", e),
+            DbError::Store(e) => write!(f, "{}", e),
+            DbError::Version(v) => write!(f, "database schema version {} is newer than this rapp1 ({})", v, DB_VERSION),
+            DbError::Row { customerid, message } => write!(f, "row '{}': {}", customerid, message),
+        }
+    }
+}
+
+impl std::error::Error for DbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DbError::Sqlite(e) => Some(e),
+            DbError::Store(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(e: rusqlite::Error) -> Self {
+        DbError::Sqlite(e)
+    }
+}
+
+impl From<StoreError> for DbError {
+    fn from(e: StoreError) -> Self {
+        DbError::Store(e)
+    }
+}
+
+// the values of the columns , in the order of COLUMNS
+fn insert_row(conn: &Connection, c: &Customer) -> Result<(), DbError> {
+    let extra = Value::Object(c.extra.clone()).to_string();
+    let sql = format!("INSERT INTO customers ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", COLUMNS);
+    let result = conn.prepare_cached(&sql)?.execute(params![
+        c.customerid.to_string(),
+        c.age,
+        c.eyecolor,
+        c.name,
+        c.gender,
+        c.company,
+        c.email,
+        c.phone,
+        c.address,
+        extra,
+    ]);
+    match result {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::ConstraintViolation => {
+            Err(DbError::Store(StoreError::DuplicateId(c.customerid)))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+// rusqlite errors can not carry our own errors , so a row is read as text first and checked after
+type RawRow = (String, Option<u32>, String, String, String, String, String, String, String, String);
+
+fn raw_row(row: &Row) -> rusqlite::Result<RawRow> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?, row.get(9)?))
+}
+
+fn to_customer(raw: RawRow) -> Result<Customer, DbError> {
+    let (id, age, eyecolor, name, gender, company, email, phone, address, extra) = raw;
+    let bad = |message: String| DbError::Row { customerid: id.clone(), message };
+    let customerid = id.parse::<ObjectId>().map_err(|e| bad(e.to_string()))?;
+    let extra: Map<String, Value> = serde_json::from_str(&extra).map_err(|e| bad(format!("extra: {}", e)))?;
+    Ok(Customer { customerid, age, eyecolor, name, gender, company, email, phone, address, extra })
+}
+
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    // open (or create) a database file , and bring its schema up to DB_VERSION
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteStore, DbError> {
+        SqliteStore::init(Connection::open(path)?)
+    }
+
+    // a database that is gone when the store is dropped
+    pub fn open_in_memory() -> Result<SqliteStore, DbError> {
+        SqliteStore::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<SqliteStore, DbError> {
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > DB_VERSION {
+            return Err(DbError::Version(version));
+        }
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(sql)?;
+            // PRAGMA does not take parameters , the version is a number we made
+            tx.execute_batch(&format!("PRAGMA user_version = {}", i + 1))?;
+            tx.commit()?;
+        }
+        Ok(SqliteStore { conn })
+    }
+
+    pub fn version(&self) -> Result<u32, DbError> {
+        Ok(self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    pub fn len(&self) -> Result<usize, DbError> {
+        let n: i64 = self.conn.query_row("SELECT COUNT(*) FROM customers", [], |row| row.get(0))?;
+        Ok(n as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, DbError> {
+        Ok(self.len()? == 0)
+    }
+
+    // the customers of a SELECT of COLUMNS
+    fn select<P: Params>(&self, sql: &str, params: P) -> Result<Vec<Customer>, DbError> {
+        let mut stmt = self.conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params, raw_row)?.collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.into_iter().map(to_customer).collect()
+    }
+
+    pub fn get(&self, customerid: &ObjectId) -> Result<Option<Customer>, DbError> {
+        let sql = format!("SELECT {} FROM customers WHERE customerid = ?1", COLUMNS);
+        let raw = self.conn.prepare_cached(&sql)?.query_row([customerid.to_string()], raw_row).optional()?;
+        raw.map(to_customer).transpose()
+    }
+
+    // lookups on the indexed columns , ignoring case
+    pub fn by_email(&self, email: &str) -> Result<Vec<Customer>, DbError> {
+        let sql = format!("SELECT {} FROM customers WHERE email = ?1 COLLATE NOCASE ORDER BY rowid", COLUMNS);
+        self.select(&sql, [email.trim()])
+    }
+
+    pub fn by_company(&self, company: &str) -> Result<Vec<Customer>, DbError> {
+        let sql = format!("SELECT {} FROM customers WHERE company = ?1 COLLATE NOCASE ORDER BY rowid", COLUMNS);
+        self.select(&sql, [company.trim()])
+    }
+
+    // every customer , in the order they were added
+    pub fn customers(&self) -> Result<Vec<Customer>, DbError> {
+        self.select(&format!("SELECT {} FROM customers ORDER BY rowid", COLUMNS), [])
+    }
+
+    // add a new customer , its customerid must not be in the database yet
+    pub fn insert(&self, c: &Customer) -> Result<(), DbError> {
+        insert_row(&self.conn, c)
+    }
+
+    // replace an existing customer (matched by customerid)
+    pub fn update(&self, c: &Customer) -> Result<(), DbError> {
+        let extra = Value::Object(c.extra.clone()).to_string();
+        let changed = self.conn.prepare_cached(
+            "UPDATE customers SET age = ?2, eyecolor = ?3, name = ?4, gender = ?5, company = ?6,
+             email = ?7, phone = ?8, address = ?9, extra = ?10 WHERE customerid = ?1",
+        )?
+        .execute(params![c.customerid.to_string(), c.age, c.eyecolor, c.name, c.gender, c.company, c.email, c.phone, c.address, extra])?;
+        match changed {
+            0 => Err(StoreError::NotFound(c.customerid).into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn delete(&self, customerid: &ObjectId) -> Result<(), DbError> {
+        match self.conn.prepare_cached("DELETE FROM customers WHERE customerid = ?1")?.execute([customerid.to_string()])? {
+            0 => Err(StoreError::NotFound(*customerid).into()),
+            _ => Ok(()),
+        }
+    }
+
+    // add all customers or none of them , the first one that fails undoes the others
+    pub fn insert_all(&mut self, customers: &[Customer]) -> Result<usize, DbError> {
+        let tx = self.conn.transaction()?;
+        for c in customers {
+            insert_row(&tx, c)?;
+        }
+        tx.commit()?;
+        Ok(customers.len())
+    }
+
+    // a JSON array , NDJSON or CSV file (see store::read_file) , in one transaction
+    pub fn import<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, DbError> {
+        let (customers, _) = store::read_file(path)?;
+        self.insert_all(&customers)
+    }
+
+    // every customer to a file , in the format of its extension
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<usize, DbError> {
+        let customers = self.customers()?;
+        store::write_file(path, &customers)?;
+        Ok(customers.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str, name: &str, company: &str) -> Customer {
+        Customer {
+            company: company.to_string(),
+            email: format!("{}@{}.com", name.split(' ').next().unwrap().to_lowercase(), company.to_lowercase()),
+            ..Customer::sample(id, name, 28)
+        }
+    }
+
+    fn temp(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rapp1-sqlite-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_crud_and_lookups() {
+        let db = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(db.version().unwrap(), DB_VERSION);
+
+        let mut mabel = customer("a", "Mabel Haley", "ENOMEN");
+        mabel.extra.insert(String::from("tier"), Value::from("gold"));
+        db.insert(&mabel).unwrap();
+        db.insert(&customer("b", "Ann Lee", "ENOMEN")).unwrap();
+        db.insert(&customer("c", "Whitney Carver", "ZILLACON")).unwrap();
+        assert!(matches!(db.insert(&mabel), Err(DbError::Store(StoreError::DuplicateId(_)))));
+
+        assert_eq!(db.get(&oid("a")).unwrap().as_ref(), Some(&mabel));
+        assert_eq!(db.get(&oid("f")).unwrap(), None);
+        assert_eq!(db.by_email("MABEL@enomen.com").unwrap(), vec![mabel.clone()]);
+        assert_eq!(db.by_company("enomen").unwrap().len(), 2);
+
+        mabel.age = Some(29);
+        db.update(&mabel).unwrap();
+        assert_eq!(db.get(&oid("a")).unwrap().unwrap().age, Some(29));
+        mabel.age = None;
+        db.update(&mabel).unwrap();
+        assert_eq!(db.get(&oid("a")).unwrap().unwrap().age, None);
+        db.delete(&oid("b")).unwrap();
+        assert!(matches!(db.delete(&oid("b")), Err(DbError::Store(StoreError::NotFound(_)))));
+        assert!(matches!(db.update(&customer("f", "Nobody", "X")), Err(DbError::Store(StoreError::NotFound(_)))));
+
+        let names: Vec<String> = db.customers().unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Mabel Haley", "Whitney Carver"]);
+    }
+
+    // the lookups use the indexes , not a scan of the whole table
+    #[test]
+    fn test_indexes() {
+        let db = SqliteStore::open_in_memory().unwrap();
+        for (filter, index) in [
+            ("customerid = ?1", "sqlite_autoindex_customers_1"),
+            ("email = ?1 COLLATE NOCASE", "customers_email"),
+            ("company = ?1 COLLATE NOCASE", "customers_company"),
+        ] {
+            let sql = format!("EXPLAIN QUERY PLAN SELECT {} FROM customers WHERE {}", COLUMNS, filter);
+            let plan: String = db.conn.query_row(&sql, ["x"], |row| row.get(3)).unwrap();
+            assert!(plan.contains(index), "{}: {}", filter, plan);
+        }
+    }
+
+    #[test]
+    fn test_migrations() {
+        let path = temp("migrations.db");
+        std::fs::remove_file(&path).ok();
+        let db = SqliteStore::open(&path).unwrap();
+        db.insert(&customer("a", "Mabel Haley", "ENOMEN")).unwrap();
+        db.insert(&Customer { age: None, ..customer("b", "Ann Lee", "ENOMEN") }).unwrap();
+        drop(db);
+        // opened again , nothing is migrated twice
+        let db = SqliteStore::open(&path).unwrap();
+        assert_eq!(db.version().unwrap(), DB_VERSION);
+        assert_eq!(db.customers().unwrap().iter().map(|c| c.age).collect::<Vec<_>>(), [Some(28), None]);
+        drop(db);
+
+        Connection::open(&path).unwrap().execute_batch("PRAGMA user_version = 99").unwrap();
+        assert!(matches!(SqliteStore::open(&path), Err(DbError::Version(99))));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_import_is_all_or_nothing() {
+        let (json, csv, out) = (temp("import.json"), temp("import.csv"), temp("export.ndjson"));
+        let customers = vec![customer("a", "Mabel Haley", "ENOMEN"), customer("b", "Ann Lee", "ENOMEN")];
+        store::write_file(&json, &customers).unwrap();
+        store::write_file(&csv, &[customer("c", "Whitney Carver", "ZILLACON"), customer("a", "Mabel Haley", "ENOMEN")]).unwrap();
+
+        let mut db = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(db.import(&json).unwrap(), 2);
+        // the second row of the CSV is already there , so the first one is not added either
+        assert!(matches!(db.import(&csv), Err(DbError::Store(StoreError::DuplicateId(_)))));
+        assert_eq!(db.len().unwrap(), 2);
+
+        assert_eq!(db.export(&out).unwrap(), 2);
+        assert_eq!(store::read_file(&out).unwrap().0, customers);
+
+        for path in [json, csv, out] {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
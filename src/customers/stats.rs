@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::customers::validate::MAX_AGE;
+use crate::model::Customer;
+
+/*
+    Stats collects counts while customers are added one at a time,
+    so a report can be made over a stream without keeping the customers around.
+
+    ages are counted per value (not kept in a list) , which is enough
+    for min , max , mean , median and the histogram.
+*/
+#[derive(Debug, Default)]
+pub struct Stats {
+    customers: usize,
+    ages: BTreeMap<u32, usize>,
+    gender: HashMap<String, usize>,
+    eyecolor: HashMap<String, usize>,
+    company: HashMap<String, usize>,
+    email_domain: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Count {
+    pub value: String,
+    pub count: usize,
+}
+
+// ages from..=to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bucket {
+    pub from: u32,
+    pub to: u32,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgeStats {
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+    pub median: f64,
+    pub histogram: Vec<Bucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub customers: usize,
+    // None when no customer has an age
+    pub age: Option<AgeStats>,
+    pub gender: Vec<Count>,
+    pub eyecolor: Vec<Count>,
+    pub company: Vec<Count>,
+    pub top_companies: Vec<Count>,
+    pub email_domains: Vec<Count>,
+}
+
+// most common first , ties in alphabetical order
+fn sorted(counts: &HashMap<String, usize>) -> Vec<Count> {
+    let mut v: Vec<Count> = counts.iter().map(|(value, &count)| Count { value: value.clone(), count }).collect();
+    v.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    v
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    pub fn add(&mut self, c: &Customer) {
+        self.customers += 1;
+        if let Some(age) = c.age {
+            *self.ages.entry(age).or_default() += 1;
+        }
+        *self.gender.entry(c.gender.clone()).or_default() += 1;
+        *self.eyecolor.entry(c.eyecolor.clone()).or_default() += 1;
+        *self.company.entry(c.company.clone()).or_default() += 1;
+
+        let domain = match c.email.rsplit_once('@') {
+            Some((_, domain)) if !domain.is_empty() => domain.to_lowercase(),
+            _ => String::from("(none)"),
+        };
+        *self.email_domain.entry(domain).or_default() += 1;
+    }
+
+    // the age at position i (0 based) , if all ages were sorted
+    fn nth_age(&self, i: usize) -> u32 {
+        let mut seen = 0;
+        for (&age, &n) in &self.ages {
+            seen += n;
+            if seen > i {
+                return age;
+            }
+        }
+        0
+    }
+
+    fn age_stats(&self, bucket_width: u32) -> Option<AgeStats> {
+        let min = *self.ages.keys().next()?;
+        let max = *self.ages.keys().next_back()?;
+        // the customers without an age are not in ages , and not in n
+        let n: usize = self.ages.values().sum();
+
+        let sum: u64 = self.ages.iter().map(|(&age, &count)| age as u64 * count as u64).sum();
+        let median = if n % 2 == 1 {
+            self.nth_age(n / 2) as f64
+        } else {
+            (self.nth_age(n / 2 - 1) as f64 + self.nth_age(n / 2) as f64) / 2.0
+        };
+
+        /*
+            buckets start at a multiple of the width : 20..=29 , 30..=39 , ... up to MAX_AGE ;
+            the ages past the bucket of MAX_AGE (bad records , 4294967295 ...) share one last bucket ,
+            that ends at u32::MAX , so there are never more buckets than MAX_AGE / width + 2
+        */
+        let width = bucket_width.max(1);
+        let mut histogram = Vec::new();
+        let mut from = Some(min / width * width);
+        while let Some(start) = from.filter(|&f| f <= max && f <= MAX_AGE) {
+            let to = start.saturating_add(width - 1);
+            let count = self.ages.range(start..=to).map(|(_, &c)| c).sum();
+            histogram.push(Bucket { from: start, to, count });
+            from = to.checked_add(1);
+        }
+        if let Some(start) = from.filter(|&f| f <= max) {
+            let count = self.ages.range(start..).map(|(_, &c)| c).sum();
+            histogram.push(Bucket { from: start, to: u32::MAX, count });
+        }
+
+        Some(AgeStats { min, max, mean: sum as f64 / n as f64, median, histogram })
+    }
+
+    pub fn report(&self, bucket_width: u32, top: usize) -> Report {
+        let company = sorted(&self.company);
+        Report {
+            customers: self.customers,
+            age: self.age_stats(bucket_width),
+            gender: sorted(&self.gender),
+            eyecolor: sorted(&self.eyecolor),
+            top_companies: company.iter().take(top).cloned().collect(),
+            company,
+            email_domains: sorted(&self.email_domain),
+        }
+    }
+}
+
+fn write_counts(f: &mut fmt::Formatter<'_>, title: &str, counts: &[Count], total: usize) -> fmt::Result {
+    writeln!(f, "\n{}", title)?;
+    for c in counts {
+        let percent = if total == 0 { 0.0 } else { c.count as f64 * 100.0 / total as f64 };
+        writeln!(f, "    {:<30} {:>8} {:>6.1}%", c.value, c.count, percent)?;
+    }
+    Ok(())
+}
+
+// the text table , with every value counted , like the JSON
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "customers : {}", self.customers)?;
+
+        if let Some(age) = &self.age {
+            writeln!(f, "\nage")?;
+            writeln!(f, "    min {} , max {} , mean {:.1} , median {:.1}", age.min, age.max, age.mean, age.median)?;
+            let widest = age.histogram.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+            for b in &age.histogram {
+                let bar = "#".repeat((b.count * 40).div_ceil(widest));
+                writeln!(f, "    {:>3} - {:<3} {:>8} {}", b.from, b.to, b.count, bar)?;
+            }
+        }
+
+        write_counts(f, "gender", &self.gender, self.customers)?;
+        write_counts(f, "eyecolor", &self.eyecolor, self.customers)?;
+        write_counts(f, "companies", &self.company, self.customers)?;
+        write_counts(f, &format!("top {} companies", self.top_companies.len()), &self.top_companies, self.customers)?;
+        write_counts(f, "email domains", &self.email_domains, self.customers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn customer(age: u32, gender: &str, company: &str, email: &str) -> Customer {
+        Customer {
+            gender: gender.to_string(),
+            company: company.to_string(),
+            email: email.to_string(),
+            ..Customer::sample("630c2272eabd3d30fe44d139", "Mabel Haley", age)
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let mut stats = Stats::new();
+        stats.add(&customer(28, "female", "ENOMEN", "a@enomen.com"));
+        stats.add(&customer(35, "male", "ZILLACON", "b@Zillacon.com"));
+        stats.add(&customer(41, "female", "ENOMEN", "c@enomen.com"));
+        stats.add(&customer(23, "female", "GEEKOLA", "broken"));
+
+        let r = stats.report(10, 2);
+        assert_eq!(r.customers, 4);
+
+        let age = r.age.as_ref().unwrap();
+        assert_eq!((age.min, age.max), (23, 41));
+        assert_eq!(age.mean, 31.75);
+        assert_eq!(age.median, 31.5);
+        assert_eq!(
+            age.histogram,
+            vec![Bucket { from: 20, to: 29, count: 2 }, Bucket { from: 30, to: 39, count: 1 }, Bucket { from: 40, to: 49, count: 1 }]
+        );
+
+        assert_eq!(r.gender[0], Count { value: String::from("female"), count: 3 });
+        assert_eq!(r.company.len(), 3);
+        assert_eq!(r.top_companies.iter().map(|c| c.value.as_str()).collect::<Vec<_>>(), vec!["ENOMEN", "GEEKOLA"]);
+        assert_eq!(r.email_domains.iter().map(|c| c.value.as_str()).collect::<Vec<_>>(), vec!["enomen.com", "(none)", "zillacon.com"]);
+
+        let text = r.to_string();
+        assert!(text.contains("median 31.5"));
+        assert!(text.contains("top 2 companies"));
+        // ZILLACON is not in the top 2 , but it is in the counts of every company
+        let companies = text.split("\ncompanies\n").nth(1).unwrap();
+        assert!(companies.lines().take(3).all(|l| ["ENOMEN", "GEEKOLA", "ZILLACON"].iter().any(|c| l.contains(c))), "{}", text);
+    }
+
+    #[test]
+    fn test_extreme_ages() {
+        let mut stats = Stats::new();
+        stats.add(&customer(28, "female", "ENOMEN", "a@enomen.com"));
+        stats.add(&customer(u32::MAX, "female", "ENOMEN", "b@enomen.com"));
+        stats.add(&customer(4_000_000_000, "female", "ENOMEN", "c@enomen.com"));
+
+        let age = stats.report(50, 5).age.unwrap();
+        assert_eq!(age.max, u32::MAX);
+        assert_eq!(
+            age.histogram,
+            vec![Bucket { from: 0, to: 49, count: 1 }, Bucket { from: 50, to: 99, count: 0 }, Bucket { from: 100, to: 149, count: 0 }, Bucket { from: 150, to: u32::MAX, count: 2 }]
+        );
+
+        // one age only , and a width that does not fit after it
+        let mut stats = Stats::new();
+        stats.add(&customer(u32::MAX, "female", "ENOMEN", "b@enomen.com"));
+        assert_eq!(stats.report(u32::MAX, 5).age.unwrap().histogram, vec![Bucket { from: u32::MAX, to: u32::MAX, count: 1 }]);
+        assert_eq!(stats.report(10, 5).age.unwrap().histogram, vec![Bucket { from: 4_294_967_290, to: u32::MAX, count: 1 }]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let r = Stats::new().report(10, 5);
+        assert_eq!(r.age, None);
+        assert!(r.to_string().starts_with("customers : 0"));
+    }
+}
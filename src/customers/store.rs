@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::model::{Customer, ObjectId};
+
+use super::csv::{self, CsvError};
+
+/*
+    Three file formats are supported :
+
+    Json   -> one JSON array , [ {customer}, {customer}, ... ]
+    Ndjson -> one customer object per line (newline-delimited JSON)
+    Csv    -> a header row , then one customer per row (see customers::csv)
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl Format {
+    // .ndjson and .jsonl files are NDJSON , .csv files are CSV , everything else is a JSON array
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Format {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("ndjson") | Some("jsonl") => Format::Ndjson,
+            Some("csv") => Format::Csv,
+            _ => Format::Json,
+        }
+    }
+
+    // a document starting with '[' is a JSON array , anything else is NDJSON (CSV is never detected)
+    pub fn detect(data: &str) -> Format {
+        match data.trim_start().chars().next() {
+            Some('[') => Format::Json,
+            _ => Format::Ndjson,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    // line is set for NDJSON input, where every record has its own line
+    Json { line: Option<usize>, source: serde_json::Error },
+    Csv(CsvError),
+    DuplicateId(ObjectId),
+    NotFound(ObjectId),
+    NoPath,
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "{}", e),
+            StoreError::Json { line: Some(line), source } => write!(f, "line {}: {}", line, source),
+            StoreError::Json { line: None, source } => write!(f, "{}", source),
+            StoreError::Csv(e) => write!(f, "{}", e),
+            StoreError::DuplicateId(id) => write!(f, "duplicate customerid '{}'", id),
+            StoreError::NotFound(id) => write!(f, "no customer with customerid '{}'", id),
+            StoreError::NoPath => write!(f, "store was not loaded from a file , use save_to"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Io(e) => Some(e),
+            StoreError::Json { source, .. } => Some(source),
+            StoreError::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+impl From<CsvError> for StoreError {
+    fn from(e: CsvError) -> Self {
+        StoreError::Csv(e)
+    }
+}
+
+/*
+    CustomerStore keeps customers in the order they were loaded (or inserted),
+    with an index from customerid to the position in that list.
+*/
+#[derive(Debug)]
+pub struct CustomerStore {
+    customers: Vec<Customer>,
+    index: HashMap<ObjectId, usize>,
+    path: Option<PathBuf>,
+    format: Format,
+}
+
+impl Default for CustomerStore {
+    fn default() -> Self {
+        CustomerStore::new()
+    }
+}
+
+impl CustomerStore {
+    pub fn new() -> CustomerStore {
+        CustomerStore {
+            customers: Vec::new(),
+            index: HashMap::new(),
+            path: None,
+            format: Format::Json,
+        }
+    }
+
+    // load a JSON array , NDJSON or CSV file (see read_file)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<CustomerStore, StoreError> {
+        let (customers, format) = read_file(path.as_ref())?;
+        let mut store = CustomerStore::from_customers(customers)?;
+        store.path = Some(path.as_ref().to_path_buf());
+        store.format = format;
+        Ok(store)
+    }
+
+    // fails on the first customerid that shows up twice
+    pub fn from_customers(customers: Vec<Customer>) -> Result<CustomerStore, StoreError> {
+        let mut store = CustomerStore::new();
+        for c in customers {
+            store.insert(c)?;
+        }
+        Ok(store)
+    }
+
+    pub fn len(&self) -> usize {
+        self.customers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.customers.is_empty()
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, customerid: &ObjectId) -> Option<&Customer> {
+        self.index.get(customerid).map(|&i| &self.customers[i])
+    }
+
+    pub fn contains(&self, customerid: &ObjectId) -> bool {
+        self.index.contains_key(customerid)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Customer> {
+        self.customers.iter()
+    }
+
+    pub fn customers(&self) -> &[Customer] {
+        &self.customers
+    }
+
+    // add a new customer , its customerid must not be in the store yet
+    pub fn insert(&mut self, customer: Customer) -> Result<(), StoreError> {
+        if self.index.contains_key(&customer.customerid) {
+            return Err(StoreError::DuplicateId(customer.customerid));
+        }
+        self.index.insert(customer.customerid, self.customers.len());
+        self.customers.push(customer);
+        Ok(())
+    }
+
+    // replace an existing customer (matched by customerid) , returns the old record
+    pub fn update(&mut self, customer: Customer) -> Result<Customer, StoreError> {
+        match self.index.get(&customer.customerid) {
+            Some(&i) => Ok(std::mem::replace(&mut self.customers[i], customer)),
+            None => Err(StoreError::NotFound(customer.customerid)),
+        }
+    }
+
+    // remove a customer , returns the removed record
+    pub fn delete(&mut self, customerid: &ObjectId) -> Result<Customer, StoreError> {
+        let i = match self.index.remove(customerid) {
+            Some(i) => i,
+            None => return Err(StoreError::NotFound(*customerid)),
+        };
+        let removed = self.customers.remove(i);
+        // everything after the removed record moved one position up
+        for pos in self.index.values_mut() {
+            if *pos > i {
+                *pos -= 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    // write back to the file the store was loaded from , in the same format
+    pub fn save(&self) -> Result<(), StoreError> {
+        match &self.path {
+            Some(path) => self.save_to(path, self.format),
+            None => Err(StoreError::NoPath),
+        }
+    }
+
+    /*
+        the data is written to a temporary file next to the target first,
+        and then renamed over it, so a failed write never leaves a half written file behind
+    */
+    pub fn save_to<P: AsRef<Path>>(&self, path: P, format: Format) -> Result<(), StoreError> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        {
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            write_customers(&mut w, &self.customers, format)?;
+            w.flush()?;
+        }
+
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a CustomerStore {
+    type Item = &'a Customer;
+    type IntoIter = std::slice::Iter<'a, Customer>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.customers.iter()
+    }
+}
+
+/*
+    read all customers of a file :
+    .csv files are read as CSV , for anything else the format is detected from the contents
+*/
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<(Vec<Customer>, Format), StoreError> {
+    if Format::from_path(path.as_ref()) == Format::Csv {
+        return Ok((csv::read_customers(File::open(path)?)?, Format::Csv));
+    }
+    let data = fs::read_to_string(path)?;
+    Ok((parse(&data)?, Format::detect(&data)))
+}
+
+// write all customers to a file , in the format that matches its extension
+pub fn write_file<P: AsRef<Path>>(path: P, customers: &[Customer]) -> Result<(), StoreError> {
+    let format = Format::from_path(path.as_ref());
+    let mut w = BufWriter::new(File::create(path)?);
+    write_customers(&mut w, customers, format)?;
+    w.flush()?;
+    Ok(())
+}
+
+// parse a JSON array or NDJSON document (blank lines are skipped)
+pub fn parse(data: &str) -> Result<Vec<Customer>, StoreError> {
+    match Format::detect(data) {
+        Format::Json => serde_json::from_str(data).map_err(|e| StoreError::Json { line: None, source: e }),
+        _ => {
+            let mut customers = Vec::new();
+            for (i, line) in data.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let c = serde_json::from_str(line).map_err(|e| StoreError::Json { line: Some(i + 1), source: e })?;
+                customers.push(c);
+            }
+            Ok(customers)
+        }
+    }
+}
+
+pub fn write_customers<W: Write>(w: &mut W, customers: &[Customer], format: Format) -> Result<(), StoreError> {
+    let json_err = |e| StoreError::Json { line: None, source: e };
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *w, customers).map_err(json_err)?;
+            writeln!(w)?;
+        }
+        Format::Ndjson => {
+            for c in customers {
+                serde_json::to_writer(&mut *w, c).map_err(json_err)?;
+                writeln!(w)?;
+            }
+        }
+        Format::Csv => csv::write_customers(w, customers)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str, name: &str) -> Customer {
+        Customer::sample(id, name, 28)
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rapp1-store-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_parse_json_and_ndjson() {
+        let a = customer("a", "Mabel Haley");
+        let b = customer("b", "Bob Stone");
+        let json = serde_json::to_string(&vec![a.clone(), b.clone()]).unwrap();
+        let ndjson = format!("{}\n\n{}\n", serde_json::to_string(&a).unwrap(), serde_json::to_string(&b).unwrap());
+
+        assert_eq!(parse(&json).unwrap(), vec![a.clone(), b.clone()]);
+        assert_eq!(parse(&ndjson).unwrap(), vec![a, b]);
+    }
+
+    #[test]
+    fn test_parse_ndjson_error_has_line() {
+        let ndjson = format!("{}\n{{\"customerid\": 1}}\n", serde_json::to_string(&customer("a", "x")).unwrap());
+        match parse(&ndjson) {
+            Err(StoreError::Json { line, .. }) => assert_eq!(line, Some(2)),
+            other => panic!("unexpected : {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_crud() {
+        let mut store = CustomerStore::new();
+        store.insert(customer("a", "Mabel Haley")).unwrap();
+        store.insert(customer("b", "Bob Stone")).unwrap();
+        store.insert(customer("c", "Carl Fox")).unwrap();
+        assert!(matches!(store.insert(customer("a", "again")), Err(StoreError::DuplicateId(_))));
+
+        let old = store.update(customer("b", "Robert Stone")).unwrap();
+        assert_eq!(old.name, "Bob Stone");
+        assert_eq!(store.get(&oid("b")).unwrap().name, "Robert Stone");
+        assert!(matches!(store.update(customer("f", "x")), Err(StoreError::NotFound(_))));
+
+        assert_eq!(store.delete(&oid("a")).unwrap().name, "Mabel Haley");
+        assert!(store.get(&oid("a")).is_none());
+        assert_eq!(store.get(&oid("c")).unwrap().name, "Carl Fox");
+        assert_eq!(store.len(), 2);
+        assert!(store.delete(&oid("a")).is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        for (name, format) in [("customers.json", Format::Json), ("customers.ndjson", Format::Ndjson), ("customers.csv", Format::Csv)] {
+            let path = temp_path(name);
+            let store = CustomerStore::from_customers(vec![customer("a", "x"), customer("b", "y")]).unwrap();
+            store.save_to(&path, format).unwrap();
+
+            let mut loaded = CustomerStore::load(&path).unwrap();
+            assert_eq!(loaded.format(), format);
+            assert_eq!(loaded.customers(), store.customers());
+
+            loaded.delete(&oid("a")).unwrap();
+            loaded.save().unwrap();
+            assert_eq!(CustomerStore::load(&path).unwrap().len(), 1);
+
+            fs::remove_file(&path).unwrap();
+        }
+    }
+}
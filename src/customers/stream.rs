@@ -0,0 +1,616 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::model::Customer;
+
+use super::csv::{self, CsvError};
+use super::store::Format;
+
+/*
+    Streaming over customer files that are too big to load :
+    CustomerReader reads one record at a time , from a JSON array or NDJSON,
+    so only the record being read is kept in memory.
+
+    A record that is not a valid Customer is a bad record , the reader
+    reports it (with its index and byte offset) and then carries on with the next one.
+    A file that is broken in a way that does not let us find the next record
+    (an unterminated array , an I/O error) ends the stream.
+*/
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    // the file cannot be read any further
+    Syntax { offset: u64, message: String },
+    // one record is not a valid Customer , the records after it can still be read
+    Record { index: usize, offset: u64, source: serde_json::Error },
+    Csv(CsvError),
+}
+
+impl StreamError {
+    // bad records can be skipped , every other error ends the stream
+    pub fn is_record(&self) -> bool {
+        matches!(self, StreamError::Record { .. })
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{}", e),
+            StreamError::Syntax { offset, message } => write!(f, "byte {}: {}", offset, message),
+            StreamError::Record { index, offset, source } => {
+                write!(f, "record {} (byte {}): {}", index + 1, offset, source)
+            }
+            StreamError::Csv(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Record { source, .. } => Some(source),
+            StreamError::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<CsvError> for StreamError {
+    fn from(e: CsvError) -> Self {
+        StreamError::Csv(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // nothing read yet , the format is not known
+    Start,
+    // inside a JSON array , a value or ']' comes next
+    ArrayValue,
+    // inside a JSON array , ',' or ']' comes next
+    ArraySeparator,
+    Ndjson,
+    Done,
+}
+
+pub struct CustomerReader<R: BufRead> {
+    reader: R,
+    state: State,
+    // bytes consumed so far
+    offset: u64,
+    // records seen so far , good and bad
+    index: usize,
+    buf: Vec<u8>,
+}
+
+impl CustomerReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(CustomerReader::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> CustomerReader<R> {
+    pub fn new(reader: R) -> CustomerReader<R> {
+        CustomerReader { reader, state: State::Start, offset: 0, index: 0, buf: Vec::new() }
+    }
+
+    // bytes read so far
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    // records read so far , bad records included
+    pub fn records(&self) -> usize {
+        self.index
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) {
+        self.reader.consume(1);
+        self.offset += 1;
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<Option<u8>> {
+        while let Some(b) = self.peek()? {
+            if !b.is_ascii_whitespace() {
+                return Ok(Some(b));
+            }
+            self.bump();
+        }
+        Ok(None)
+    }
+
+    fn syntax<T>(&mut self, message: impl Into<String>) -> Option<Result<T, StreamError>> {
+        self.state = State::Done;
+        Some(Err(StreamError::Syntax { offset: self.offset, message: message.into() }))
+    }
+
+    fn parse_record(&mut self, offset: u64) -> Result<Customer, StreamError> {
+        let index = self.index;
+        self.index += 1;
+        serde_json::from_slice(&self.buf).map_err(|source| StreamError::Record { index, offset, source })
+    }
+
+    /*
+        copy one JSON value of the array into buf ,
+        keeping track of nesting and strings , so that commas and brackets inside them are skipped
+    */
+    fn read_array_value(&mut self) -> Result<(), StreamError> {
+        self.buf.clear();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escape = false;
+
+        loop {
+            let b = match self.peek()? {
+                Some(b) => b,
+                None => return Err(StreamError::Syntax { offset: self.offset, message: String::from("unexpected end of input inside the array") }),
+            };
+
+            if in_string {
+                self.buf.push(b);
+                self.bump();
+                if escape {
+                    escape = false;
+                } else if b == b'\\' {
+                    escape = true;
+                } else if b == b'"' {
+                    in_string = false;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                continue;
+            }
+
+            match b {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth == 0 => return Ok(()),
+                b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.buf.push(b);
+                        self.bump();
+                        return Ok(());
+                    }
+                }
+                b',' if depth == 0 => return Ok(()),
+                b if b.is_ascii_whitespace() && depth == 0 => return Ok(()),
+                _ => {}
+            }
+            self.buf.push(b);
+            self.bump();
+        }
+    }
+
+    fn next_array_record(&mut self) -> Option<Result<Customer, StreamError>> {
+        loop {
+            let b = match self.skip_whitespace() {
+                Ok(Some(b)) => b,
+                Ok(None) => return self.syntax("unexpected end of input , the array is not closed"),
+                Err(e) => return self.syntax(e.to_string()),
+            };
+
+            match (self.state, b) {
+                (State::ArraySeparator, b',') => {
+                    self.bump();
+                    self.state = State::ArrayValue;
+                }
+                (_, b']') => {
+                    self.bump();
+                    self.state = State::Done;
+                    return None;
+                }
+                (State::ArraySeparator, other) => {
+                    return self.syntax(format!("expected ',' or ']' , found '{}'", other as char));
+                }
+                (_, b',') => return self.syntax("expected a value , found ','"),
+                _ => {
+                    let offset = self.offset;
+                    if let Err(e) = self.read_array_value() {
+                        self.state = State::Done;
+                        return Some(Err(e));
+                    }
+                    self.state = State::ArraySeparator;
+                    return Some(self.parse_record(offset));
+                }
+            }
+        }
+    }
+
+    fn next_ndjson_record(&mut self) -> Option<Result<Customer, StreamError>> {
+        loop {
+            let offset = self.offset;
+            self.buf.clear();
+            let n = match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(n) => n,
+                Err(e) => return self.syntax(e.to_string()),
+            };
+            if n == 0 {
+                self.state = State::Done;
+                return None;
+            }
+            self.offset += n as u64;
+            if self.buf.iter().all(|b| b.is_ascii_whitespace()) {
+                continue; // blank lines are not records
+            }
+            return Some(self.parse_record(offset));
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CustomerReader<R> {
+    type Item = Result<Customer, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.state {
+            State::Start => {
+                // a file starting with '[' is a JSON array , anything else is NDJSON
+                match self.skip_whitespace() {
+                    Ok(Some(b'[')) => {
+                        self.bump();
+                        self.state = State::ArrayValue;
+                    }
+                    Ok(Some(_)) => self.state = State::Ndjson,
+                    Ok(None) => {
+                        self.state = State::Done;
+                        return None;
+                    }
+                    Err(e) => return self.syntax(e.to_string()),
+                }
+                self.next()
+            }
+            State::ArrayValue | State::ArraySeparator => self.next_array_record(),
+            State::Ndjson => self.next_ndjson_record(),
+            State::Done => None,
+        }
+    }
+}
+
+/*
+    CustomerWriter writes one customer at a time , as a JSON array , NDJSON or CSV.
+    finish() must be called at the end, it closes the JSON array and flushes.
+*/
+pub struct CustomerWriter<W: Write> {
+    out: Output<W>,
+    written: usize,
+}
+
+enum Output<W: Write> {
+    Json(W),
+    Ndjson(W),
+    Csv(Box<::csv::Writer<W>>),
+}
+
+/*
+    PendingFile -> a file that only replaces path when it is complete , like CustomerStore::save_to :
+    it is written to <path>.tmp and renamed over path by commit().
+    dropped without commit() (an error half way) the .tmp is removed and path is left as it was ,
+    so the output can even be the input that is still being read
+*/
+pub struct PendingFile {
+    // None once committed
+    w: Option<BufWriter<File>>,
+    tmp: PathBuf,
+    path: PathBuf,
+}
+
+impl PendingFile {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<PendingFile> {
+        let path = path.as_ref().to_path_buf();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        Ok(PendingFile { w: Some(BufWriter::new(File::create(&tmp)?)), tmp, path })
+    }
+
+    pub fn commit(mut self) -> io::Result<()> {
+        if let Some(mut w) = self.w.take() {
+            w.flush()?;
+            // closed before the rename
+            drop(w);
+            fs::rename(&self.tmp, &self.path)?;
+        }
+        Ok(())
+    }
+
+    fn writer(&mut self) -> io::Result<&mut BufWriter<File>> {
+        self.w.as_mut().ok_or_else(|| io::Error::other("the file is committed already"))
+    }
+}
+
+impl Write for PendingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer()?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer()?.flush()
+    }
+}
+
+impl Drop for PendingFile {
+    fn drop(&mut self) {
+        if self.w.take().is_some() {
+            fs::remove_file(&self.tmp).ok();
+        }
+    }
+}
+
+impl CustomerWriter<PendingFile> {
+    // create a file , the format is picked by its extension ; it is only there after commit()
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let format = Format::from_path(path.as_ref());
+        Ok(CustomerWriter::new(PendingFile::create(path)?, format))
+    }
+
+    // finish() , then put the file in place
+    pub fn commit(self) -> Result<(), StreamError> {
+        Ok(self.finish()?.commit()?)
+    }
+}
+
+impl<W: Write> CustomerWriter<W> {
+    pub fn new(w: W, format: Format) -> CustomerWriter<W> {
+        let out = match format {
+            Format::Json => Output::Json(w),
+            Format::Ndjson => Output::Ndjson(w),
+            Format::Csv => Output::Csv(Box::new(::csv::Writer::from_writer(w))),
+        };
+        CustomerWriter { out, written: 0 }
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    pub fn write(&mut self, c: &Customer) -> Result<(), StreamError> {
+        let json_err = |e: serde_json::Error| StreamError::Io(e.into());
+        match &mut self.out {
+            Output::Json(w) => {
+                w.write_all(if self.written == 0 { b"[\n  " } else { b",\n  " })?;
+                serde_json::to_writer(&mut *w, c).map_err(json_err)?;
+            }
+            Output::Ndjson(w) => {
+                serde_json::to_writer(&mut *w, c).map_err(json_err)?;
+                w.write_all(b"\n")?;
+            }
+            Output::Csv(w) => csv::write_row(w, c, self.written == 0).map_err(CsvError::from)?,
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<W, StreamError> {
+        match self.out {
+            Output::Json(mut w) => {
+                w.write_all(if self.written == 0 { b"[]\n" } else { b"\n]\n" })?;
+                w.flush()?;
+                Ok(w)
+            }
+            Output::Ndjson(mut w) => {
+                w.flush()?;
+                Ok(w)
+            }
+            Output::Csv(w) => w.into_inner().map_err(|e| StreamError::Io(e.into_error())),
+        }
+    }
+}
+
+// what a streaming operation did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub read: usize,
+    pub written: usize,
+    pub bad: usize,
+}
+
+/*
+    run 'each' for every good record.
+    with skip_bad , bad records are passed to on_bad and skipped ,
+    without it the first bad record ends the operation with an error.
+*/
+fn drive<R, B, F>(reader: CustomerReader<R>, skip_bad: bool, mut on_bad: B, mut each: F) -> Result<Summary, StreamError>
+where
+    R: BufRead,
+    B: FnMut(&StreamError),
+    F: FnMut(Customer, &mut Summary) -> Result<(), StreamError>,
+{
+    let mut summary = Summary::default();
+    for record in reader {
+        match record {
+            Ok(c) => {
+                summary.read += 1;
+                each(c, &mut summary)?;
+            }
+            Err(e) if e.is_record() && skip_bad => {
+                summary.bad += 1;
+                on_bad(&e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(summary)
+}
+
+// call f for every good record
+pub fn for_each<R, B, F>(reader: CustomerReader<R>, skip_bad: bool, on_bad: B, mut f: F) -> Result<Summary, StreamError>
+where
+    R: BufRead,
+    B: FnMut(&StreamError),
+    F: FnMut(Customer) -> Result<(), StreamError>,
+{
+    drive(reader, skip_bad, on_bad, |c, _| f(c))
+}
+
+pub fn count<R, B>(reader: CustomerReader<R>, skip_bad: bool, on_bad: B) -> Result<Summary, StreamError>
+where
+    R: BufRead,
+    B: FnMut(&StreamError),
+{
+    drive(reader, skip_bad, on_bad, |_, _| Ok(()))
+}
+
+/*
+    write f(customer) for every customer , f can change the record ,
+    or return None to leave it out
+*/
+pub fn transform<R, W, B, F>(reader: CustomerReader<R>, writer: &mut CustomerWriter<W>, skip_bad: bool, on_bad: B, mut f: F) -> Result<Summary, StreamError>
+where
+    R: BufRead,
+    W: Write,
+    B: FnMut(&StreamError),
+    F: FnMut(Customer) -> Option<Customer>,
+{
+    drive(reader, skip_bad, on_bad, |c, summary| {
+        if let Some(c) = f(c) {
+            writer.write(&c)?;
+            summary.written += 1;
+        }
+        Ok(())
+    })
+}
+
+// copy the customers for which 'keep' returns true to the writer
+pub fn filter<R, W, B, F>(reader: CustomerReader<R>, writer: &mut CustomerWriter<W>, skip_bad: bool, on_bad: B, mut keep: F) -> Result<Summary, StreamError>
+where
+    R: BufRead,
+    W: Write,
+    B: FnMut(&StreamError),
+    F: FnMut(&Customer) -> bool,
+{
+    transform(reader, writer, skip_bad, on_bad, |c| if keep(&c) { Some(c) } else { None })
+}
+
+// copy every customer to the writer , the writer decides the output format
+pub fn convert<R, W, B>(reader: CustomerReader<R>, writer: &mut CustomerWriter<W>, skip_bad: bool, on_bad: B) -> Result<Summary, StreamError>
+where
+    R: BufRead,
+    W: Write,
+    B: FnMut(&StreamError),
+{
+    filter(reader, writer, skip_bad, on_bad, |_| true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ObjectId;
+
+    // "b" -> 00000000000000000000000b
+    fn oid(s: &str) -> ObjectId {
+        format!("{:0>24}", s).parse().unwrap()
+    }
+
+    fn customer(id: &str) -> Customer {
+        Customer::sample(id, "Mabel \"]\" Haley", 28)
+    }
+
+    fn read_all(data: &str) -> Vec<Result<Customer, StreamError>> {
+        CustomerReader::new(data.as_bytes()).collect()
+    }
+
+    #[test]
+    fn test_pending_file() {
+        let path = std::env::temp_dir().join(format!("rapp1-pending-{}.json", std::process::id()));
+        std::fs::write(&path, "old").unwrap();
+
+        // not committed , the old file stays and the .tmp is gone
+        let mut writer = CustomerWriter::create(&path).unwrap();
+        writer.write(&customer("a")).unwrap();
+        drop(writer);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let mut writer = CustomerWriter::create(&path).unwrap();
+        writer.write(&customer("a")).unwrap();
+        writer.commit().unwrap();
+        let records: Vec<Customer> = CustomerReader::open(&path).unwrap().map(Result::unwrap).collect();
+        assert_eq!(records, vec![customer("a")]);
+        assert!(!path.with_extension("json.tmp").exists());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_json_array() {
+        let data = serde_json::to_string_pretty(&vec![customer("a"), customer("b")]).unwrap();
+        let records: Vec<Customer> = read_all(&data).into_iter().map(Result::unwrap).collect();
+        assert_eq!(records, vec![customer("a"), customer("b")]);
+
+        assert!(read_all("[]").is_empty());
+        assert!(read_all("  ").is_empty());
+    }
+
+    #[test]
+    fn test_bad_records_are_reported_and_skipped() {
+        let a = serde_json::to_string(&customer("a")).unwrap();
+        let data = format!("[{}, {{\"customerid\": 5}}, 42, {}]", a, a);
+        let records = read_all(&data);
+        assert_eq!(records.len(), 4);
+        assert!(records[0].is_ok() && records[3].is_ok());
+        match &records[1] {
+            Err(StreamError::Record { index, offset, .. }) => {
+                assert_eq!(*index, 1);
+                assert_eq!(*offset as usize, a.len() + 3);
+            }
+            other => panic!("unexpected : {:?}", other),
+        }
+        assert!(matches!(records[2], Err(StreamError::Record { index: 2, .. })));
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let a = serde_json::to_string(&customer("a")).unwrap();
+        let data = format!("{}\n\nnot json\n{}\n", a, a);
+        let records = read_all(&data);
+        assert_eq!(records.len(), 3);
+        match &records[1] {
+            Err(StreamError::Record { index, offset, .. }) => assert_eq!((*index, *offset as usize), (1, a.len() + 2)),
+            other => panic!("unexpected : {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_broken_array_ends_the_stream() {
+        let a = serde_json::to_string(&customer("a")).unwrap();
+        let records = read_all(&format!("[{} {}]", a, a));
+        assert_eq!(records.len(), 2);
+        assert!(matches!(records[1], Err(StreamError::Syntax { .. })));
+
+        let records = read_all(&format!("[{},", a));
+        assert!(matches!(records.last(), Some(Err(StreamError::Syntax { .. }))));
+    }
+
+    #[test]
+    fn test_filter_and_convert() {
+        let data = format!("{}\nnot json\n{}\n", serde_json::to_string(&customer("a")).unwrap(), serde_json::to_string(&customer("b")).unwrap());
+
+        let mut bad = 0;
+        let mut writer = CustomerWriter::new(Vec::new(), Format::Json);
+        let summary = filter(CustomerReader::new(data.as_bytes()), &mut writer, true, |_| bad += 1, |c| c.customerid == oid("b")).unwrap();
+        assert_eq!(summary, Summary { read: 2, written: 1, bad: 1 });
+        assert_eq!(bad, 1);
+        let out: Vec<Customer> = serde_json::from_slice(&writer.finish().unwrap()).unwrap();
+        assert_eq!(out, vec![customer("b")]);
+
+        let mut writer = CustomerWriter::new(Vec::new(), Format::Csv);
+        assert!(convert(CustomerReader::new(data.as_bytes()), &mut writer, false, |_| {}).is_err());
+
+        assert_eq!(count(CustomerReader::new(data.as_bytes()), true, |_| {}).unwrap().read, 2);
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+
+use crate::model::{Customer, ObjectId, PhoneNumber};
+
+pub const MIN_AGE: u32 = 1;
+pub const MAX_AGE: u32 = 120;
+
+pub const GENDERS: &[&str] = &["male", "female", "other"];
+
+// the patterns are also written into the JSON Schema of a customer (see customers::jsonschema)
+pub const NAME_PATTERN: &str = r"^\p{L}[\p{L} .'-]*$";
+pub const EYECOLOR_PATTERN: &str = r"^[a-z]+$";
+pub const EMAIL_PATTERN: &str = r"^[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$";
+// the shape of E.164 only , the validator also checks the number itself (see check_phone)
+pub const PHONE_PATTERN: &str = r"^\+[1-9][0-9]{6,14}$";
+
+// one broken rule , on one field of one customer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &'static str, message: impl Into<String>) -> FieldError {
+        FieldError { field, message: message.into() }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+// the result of validating one record of a file
+#[derive(Debug, Clone)]
+pub struct RecordReport {
+    pub index: usize,
+    pub customerid: ObjectId,
+    pub errors: Vec<FieldError>,
+}
+
+impl RecordReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/*
+    Validator holds the compiled regular expressions ,
+    so that they are built once and not for every record.
+
+    validate() checks every field and returns all the errors it found,
+    not only the first one.
+    customerid is not checked here , a Customer can only hold a valid ObjectId.
+*/
+pub struct Validator {
+    name: Regex,
+    eyecolor: Regex,
+    email: Regex,
+    // used for phone numbers written without a country code
+    default_country: Option<u16>,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Validator::new()
+    }
+}
+
+impl Validator {
+    pub fn new() -> Validator {
+        // the patterns are constants , so unwrap() can only fail on a typo here
+        Validator {
+            name: Regex::new(NAME_PATTERN).unwrap(),
+            eyecolor: Regex::new(EYECOLOR_PATTERN).unwrap(),
+            email: Regex::new(EMAIL_PATTERN).unwrap(),
+            default_country: None,
+        }
+    }
+
+    // accept phone numbers without a country code , as numbers of this country
+    pub fn with_default_country(mut self, country_code: u16) -> Validator {
+        self.default_country = Some(country_code);
+        self
+    }
+
+    pub fn validate(&self, c: &Customer) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        // no age is not an error , a record can leave it out
+        if let Some(age) = c.age.filter(|a| !(MIN_AGE..=MAX_AGE).contains(a)) {
+            errors.push(FieldError::new("age", format!("{} is outside {}..={}", age, MIN_AGE, MAX_AGE)));
+        }
+
+        if !self.eyecolor.is_match(&c.eyecolor) {
+            errors.push(FieldError::new("eyecolor", format!("'{}' is not a lowercase color name", c.eyecolor)));
+        }
+
+        if c.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "is empty"));
+        } else if c.name != c.name.trim() || !self.name.is_match(&c.name) {
+            errors.push(FieldError::new("name", format!("'{}' contains characters that are not allowed in a name", c.name)));
+        }
+
+        if !GENDERS.contains(&c.gender.as_str()) {
+            errors.push(FieldError::new("gender", format!("'{}' is not one of {}", c.gender, GENDERS.join(", "))));
+        }
+
+        if c.company.trim().is_empty() {
+            errors.push(FieldError::new("company", "is empty"));
+        }
+
+        if !self.email.is_match(&c.email) {
+            errors.push(FieldError::new("email", format!("'{}' is not a valid email address", c.email)));
+        }
+
+        if let Some(message) = self.check_phone(&c.phone) {
+            errors.push(FieldError::new("phone", message));
+        }
+
+        if c.address.trim().is_empty() {
+            errors.push(FieldError::new("address", "is empty"));
+        } else if let Err(e) = c.parsed_address() {
+            errors.push(FieldError::new("address", e.to_string()));
+        }
+
+        errors
+    }
+
+    /*
+        a stored phone number is in E.164 , "+18805162365" , and nothing else :
+        "+1 (880) 516-2365" parses , but it is written for people and not for other systems ,
+        so it is an error that shows the E.164 form ('customers convert --normalize-phones' writes it).
+        the default country only helps that suggestion , for numbers without a country code
+    */
+    fn check_phone(&self, phone: &str) -> Option<String> {
+        match PhoneNumber::parse(phone, self.default_country) {
+            Ok(p) if p.e164() == phone => None,
+            Ok(p) => Some(format!("'{}' is not in E.164 form , write it as {}", phone, p.e164())),
+            Err(e) => Some(format!("'{}' {}", phone, e)),
+        }
+    }
+
+    /*
+        validate every record of a file,
+        a customerid that was already seen in an earlier record is an error as well
+    */
+    pub fn validate_all(&self, customers: &[Customer]) -> Vec<RecordReport> {
+        let mut seen: HashMap<ObjectId, usize> = HashMap::new();
+        let mut reports = Vec::with_capacity(customers.len());
+
+        for (i, c) in customers.iter().enumerate() {
+            let mut errors = self.validate(c);
+            match seen.get(&c.customerid) {
+                Some(first) => errors.push(FieldError::new("customerid", format!("duplicate of record {}", first + 1))),
+                None => {
+                    seen.insert(c.customerid, i);
+                }
+            }
+            reports.push(RecordReport { index: i, customerid: c.customerid, errors });
+        }
+
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mabel() -> Customer {
+        Customer::sample("630c2272eabd3d30fe44d139", "Mabel Haley", 28)
+    }
+
+    fn fields(errors: &[FieldError]) -> Vec<&'static str> {
+        errors.iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn test_valid_customer() {
+        assert!(Validator::new().validate(&mabel()).is_empty());
+        // a record without an age is valid , only an age that is there is checked
+        assert!(Validator::new().validate(&Customer { age: None, ..mabel() }).is_empty());
+    }
+
+    #[test]
+    fn test_all_errors_are_reported() {
+        let mut c = mabel();
+        c.age = Some(0);
+        c.eyecolor = String::new();
+        c.name = String::from("   ");
+        c.gender = String::from("f");
+        c.company = String::new();
+        c.email = String::from("mabel@");
+        c.phone = String::from("+1 (180) 516-2365");
+        c.address = String::new();
+
+        let errors = Validator::new().validate(&c);
+        assert_eq!(
+            fields(&errors),
+            vec!["age", "eyecolor", "name", "gender", "company", "email", "phone", "address"]
+        );
+    }
+
+    #[test]
+    fn test_phone() {
+        let v = Validator::new();
+        assert!(v.check_phone("+18805162365").is_none());
+        assert_eq!(v.check_phone("+1 (880) 516-2365").unwrap(), "'+1 (880) 516-2365' is not in E.164 form , write it as +18805162365");
+        assert!(v.check_phone(" +18805162365").is_some());
+        assert!(v.check_phone("516-23").is_some());
+        assert!(v.check_phone("+1 880 CALL-NOW").is_some());
+        assert!(v.check_phone("(880) 516-2365").is_some());
+        let v = v.with_default_country(1);
+        assert!(v.check_phone("(880) 516-2365").unwrap().ends_with("write it as +18805162365"));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let reports = Validator::new().validate_all(&[mabel(), mabel()]);
+        assert!(reports[0].is_valid());
+        assert_eq!(reports[1].errors, vec![FieldError::new("customerid", "duplicate of record 1")]);
+    }
+}
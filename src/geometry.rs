@@ -0,0 +1,39 @@
+#[derive(Debug)] // this will allow printing of structs
+pub struct Rectangle {
+    pub width: u32,
+    pub height: u32
+}
+
+impl Rectangle {
+    pub fn print_description(&self) {
+        println!("Rectangle : {} x {}", self.width, self.height)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.height == self.width
+    }
+}
+
+// struct - used for testing
+pub struct MyBox {
+    pub width: u32,
+    pub height: u32
+}
+
+// used by Box struct - for testing
+impl MyBox {
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rectangle_is_square() {
+        assert!(Rectangle { width: 10, height: 10 }.is_square());
+        assert!(!Rectangle { width: 10, height: 11 }.is_square());
+    }
+}
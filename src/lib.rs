@@ -0,0 +1,91 @@
+/*
+    rapp1 as a library :
+
+    model    -> the data types (Customer, Person, Color, Day, Direction ...)
+    text     -> small string / matching helpers
+    net      -> http requests , HttpClient and the shared tokio runtime
+    sys      -> files and external commands
+    geometry -> shapes (Rectangle, MyBox)
+
+    customers -> customer tooling on top of model::Customer (store, ...)
+    server    -> HTTP API over a customer store
+
+    sections -> the runnable examples, one function per section
+    cli      -> turns command line arguments into a section to run
+
+    src/main.rs only collects the arguments and hands them to cli::run
+*/
+
+extern crate rand; // for random number , make sure you add (rand = "0.8.5") in Cargo.toml first
+extern crate regex; // make sure you add (regex = "1.7.0") in Cargo.toml first
+extern crate reqwest;
+extern crate serde_json; // for parsing json
+extern crate serde; // for parsing json
+
+#[macro_use]
+extern crate serde_derive;
+
+pub mod cli;
+pub mod customers;
+pub mod dcode;
+pub mod geometry;
+pub mod model;
+pub mod net;
+pub mod sections;
+#[allow(clippy::result_large_err)] // handlers return an error Response as Err , it is built once per request
+pub mod server;
+pub mod sys;
+pub mod text;
+
+// for 'cargo test', used in below testing module
+#[cfg(test)]
+fn get_data_for_testing(d: u32) -> u32 {
+    d
+}
+
+// Run : cargo test
+#[cfg(test)] // cargo build will also compile this module, to avoid this , add this line
+mod my_testing {
+    // use core::panicking::panic;
+    #[test]
+    fn test_basic() {
+        assert_eq!(1, 1);
+    }
+
+    #[test]
+    fn test_basic_no_equal() {
+        assert_ne!(super::get_data_for_testing(2), 3);
+    }
+
+    #[test]
+    #[ignore] // this test will be ignored ('cargo test' will ignore it)
+    fn test_basic_ignore() {
+        assert_ne!(2, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_panic() {
+        panic!("holy shit !");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_structs_fail() {
+        let box1 = crate::geometry::MyBox{
+            width: 50,
+            height: 25
+        };
+        assert!(box1.is_square());
+    }
+
+    #[test]
+    fn test_structs_pass() {
+        let box2 = crate::geometry::MyBox{
+            width: 50,
+            height: 50
+        };
+        assert!(box2.is_square());
+    }
+
+}
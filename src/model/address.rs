@@ -0,0 +1,126 @@
+use std::fmt;
+use std::str::FromStr;
+
+/*
+    Address -> the structured form of Customer.address
+
+    "184 Bergen Court, Gorham, American Samoa, 8722"
+     ^^^ ^^^^^^^^^^^^  ^^^^^^  ^^^^^^^^^^^^^^  ^^^^
+     |   street        city    region          postal_code
+     street_number
+
+    parsing and then printing (Display) an address gives back the same string
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Address {
+    pub street_number: String,
+    pub street: String,
+    pub city: String,
+    pub region: String,
+    pub postal_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    // expected "number street, city, region, postal code"
+    Parts(usize),
+    StreetNumber(String),
+    Empty(&'static str),
+    PostalCode(String),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressError::Parts(n) => write!(f, "expected 4 comma separated parts (street, city, region, postal code), found {}", n),
+            AddressError::StreetNumber(s) => write!(f, "'{}' does not start with a street number", s),
+            AddressError::Empty(part) => write!(f, "{} is empty", part),
+            AddressError::PostalCode(s) => write!(f, "'{}' is not a postal code", s),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        let (first, city, region, postal_code) = match parts[..] {
+            [first, city, region, postal_code] => (first, city, region, postal_code),
+            _ => return Err(AddressError::Parts(parts.len())),
+        };
+
+        // "184 Bergen Court" -> "184" + "Bergen Court" , a number may end with a letter : "12A"
+        let (street_number, street) = match first.split_once(' ') {
+            Some((n, street)) if is_street_number(n) => (n, street.trim()),
+            _ => return Err(AddressError::StreetNumber(first.to_string())),
+        };
+
+        for (part, value) in [("street", street), ("city", city), ("region", region), ("postal_code", postal_code)] {
+            if value.is_empty() {
+                return Err(AddressError::Empty(part));
+            }
+        }
+
+        let postal_ok = postal_code.chars().any(|c| c.is_ascii_digit())
+            && postal_code.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-');
+        if !postal_ok {
+            return Err(AddressError::PostalCode(postal_code.to_string()));
+        }
+
+        Ok(Address {
+            street_number: street_number.to_string(),
+            street: street.to_string(),
+            city: city.to_string(),
+            region: region.to_string(),
+            postal_code: postal_code.to_string(),
+        })
+    }
+}
+
+fn is_street_number(s: &str) -> bool {
+    let digits = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    !digits.is_empty() && s.len() - digits.len() <= 1 && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}, {}, {}, {}", self.street_number, self.street, self.city, self.region, self.postal_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let s = "184 Bergen Court, Gorham, American Samoa, 8722";
+        let a: Address = s.parse().unwrap();
+        assert_eq!(a.street_number, "184");
+        assert_eq!(a.street, "Bergen Court");
+        assert_eq!(a.city, "Gorham");
+        assert_eq!(a.region, "American Samoa");
+        assert_eq!(a.postal_code, "8722");
+        assert_eq!(a.to_string(), s);
+
+        let b: Address = "12A High Street,London , England, SW1A 1AA".parse().unwrap();
+        assert_eq!(b.to_string(), "12A High Street, London, England, SW1A 1AA");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!("184 Bergen Court, Gorham".parse::<Address>(), Err(AddressError::Parts(2)));
+        assert_eq!(
+            "Bergen Court, Gorham, American Samoa, 8722".parse::<Address>(),
+            Err(AddressError::StreetNumber(String::from("Bergen Court")))
+        );
+        assert_eq!("184 Bergen Court, , American Samoa, 8722".parse::<Address>(), Err(AddressError::Empty("city")));
+        assert_eq!(
+            "184 Bergen Court, Gorham, American Samoa, n/a".parse::<Address>(),
+            Err(AddressError::PostalCode(String::from("n/a")))
+        );
+    }
+}
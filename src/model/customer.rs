@@ -0,0 +1,107 @@
+use serde_json::{Map, Value};
+
+use super::{Address, AddressError, ObjectId};
+
+// Serialize and Deserialize are written by hand in model::schema , to handle older record versions
+#[derive(Debug,Clone,PartialEq)]
+pub struct Customer {
+    // not a valid ObjectId -> the customer does not deserialize
+    pub customerid: ObjectId,
+    // None when the record has no age
+    pub age: Option<u32>,
+    pub eyecolor: String,
+    pub name: String,
+    pub gender: String,
+    pub company: String,
+    pub email: String,
+    pub phone: String,
+    pub address: String,
+    // keys of the record that are not fields , kept so they are written back
+    pub extra: Map<String, Value>,
+}
+
+impl Customer {
+    // the names of the fields , in the order they are declared
+    pub const FIELDS: &'static [&'static str] = &[
+        "customerid", "age", "eyecolor", "name", "gender", "company", "email", "phone", "address",
+    ];
+
+    // the value of a field by name , as text ; None for a name that is not a field
+    pub fn field(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "customerid" => return Some(self.customerid.to_string()),
+            // "" for a customer without an age
+            "age" => return Some(self.age.map(|a| a.to_string()).unwrap_or_default()),
+            "eyecolor" => &self.eyecolor,
+            "name" => &self.name,
+            "gender" => &self.gender,
+            "company" => &self.company,
+            "email" => &self.email,
+            "phone" => &self.phone,
+            "address" => &self.address,
+            _ => return None,
+        };
+        Some(value.clone())
+    }
+
+    // the address split into its parts , see model::Address
+    pub fn parsed_address(&self) -> Result<Address, AddressError> {
+        self.address.parse()
+    }
+}
+
+/*
+    for the tests of every module : one complete , valid customer , Mabel Haley of ENOMEN ,
+    with the id , name and age given ; a short id is padded with zeros , "a" -> "00..0a".
+    the fields a test cares about are set on top of it :
+    Customer { phone: String::from("..."), ..Customer::sample("a", "Mabel Haley", 28) }
+*/
+#[cfg(test)]
+impl Customer {
+    pub fn sample(id: &str, name: &str, age: u32) -> Customer {
+        Customer {
+            customerid: format!("{:0>24}", id).parse().expect("a test id is 1 to 24 hex digits"),
+            age: Some(age),
+            eyecolor: String::from("brown"),
+            name: name.to_string(),
+            gender: String::from("female"),
+            company: String::from("ENOMEN"),
+            email: String::from("mabelhaley@enomen.com"),
+            phone: String::from("+18805162365"),
+            address: String::from("184 Bergen Court, Gorham, American Samoa, 8722"),
+            extra: Map::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_customer_from_json() {
+        let json_str = r#"
+            {
+                "customerid": "630c2272eabd3d30fe44d139",
+                "age": 28,
+                "eyecolor": "brown",
+                "name": "Mabel Haley",
+                "gender": "female",
+                "company": "ENOMEN",
+                "email": "mabelhaley@enomen.com",
+                "phone": "+1 (880) 516-2365",
+                "address": "184 Bergen Court, Gorham, American Samoa, 8722"
+            }
+        "#;
+        let c: Customer = serde_json::from_str(json_str).unwrap();
+        assert_eq!(c.customerid.to_string(), "630c2272eabd3d30fe44d139");
+        assert_eq!(c.customerid.timestamp(), 0x630c2272);
+        assert_eq!(c.age, Some(28));
+
+        for field in Customer::FIELDS {
+            assert!(c.field(field).is_some(), "{}", field);
+        }
+        assert_eq!(c.field("age").as_deref(), Some("28"));
+        assert_eq!(c.field("nope"), None);
+    }
+}
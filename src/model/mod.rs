@@ -0,0 +1,104 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+mod address;
+mod customer;
+mod object_id;
+mod phone;
+mod schema;
+
+pub use self::address::{Address, AddressError};
+pub use self::customer::Customer;
+pub use self::object_id::{ObjectId, ObjectIdError};
+pub use self::phone::{is_country_code, PhoneError, PhoneNumber, NANP};
+pub use self::schema::{migrate, RecordField, SCHEMA_VERSION};
+
+#[allow(dead_code)] // this will suppress unused variable warnings
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right
+}
+
+// enum methods
+
+pub enum Day {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday
+}
+
+// enum methods
+impl Day {
+    pub fn is_weekday(&self) -> bool {
+        !matches!(self, Day::Saturday | Day::Sunday)
+    }
+}
+
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8
+}
+
+pub struct TupleColor(pub u8, pub u8, pub u8);
+
+pub fn print_color(c: &Color) {
+    println!("color values are : {}, {}, {}", c.red, c.green, c.blue)
+}
+
+#[derive(Debug)] // so that we can print it
+pub struct Person {
+    pub name: String,
+    pub age: u8
+}
+
+// implementing traits
+
+// implementing Display also gives us to_string() for free
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "My Name Is {}, & I am {}", self.name, self.age)
+    }
+}
+
+// implement 'HasVoiceBox' trait, on 'Person' struct
+pub trait HasVoiceBox {
+    // speak
+    fn speak(&self);
+    // can speak
+    fn can_speak(&self) -> bool;
+}
+// implement 'HasVoiceBox' trait, on 'Person' struct
+impl HasVoiceBox for Person {
+    fn speak(&self) {
+        println!("Hello, my name is {} and my age is {}", self.name, self.age);
+    }
+
+    fn can_speak(&self) -> bool {
+        self.age > 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weekday() {
+        assert!(Day::Tuesday.is_weekday());
+        assert!(!Day::Sunday.is_weekday());
+    }
+
+    #[test]
+    fn test_person() {
+        let p = Person { name: String::from("Giri2"), age: 3 };
+        assert!(p.can_speak());
+        assert_eq!(p.to_string(), "My Name Is Giri2, & I am 3");
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/*
+    a MongoDB style ObjectId , 12 bytes written as 24 hex digits :
+
+        630c2272  eabd3d30fe  44d139
+        |         |           |
+        |         |           counter , 3 bytes , incremented for every new id
+        |         random , 5 bytes , the same for every id made by one process
+        seconds since 1970 , 4 bytes , big endian
+
+    the timestamp comes first , so ordering ObjectIds orders them by creation time
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectId([u8; 12]);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectIdError {
+    Length(usize),
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for ObjectIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectIdError::Length(n) => write!(f, "an ObjectId has 24 hex digits , not {}", n),
+            ObjectIdError::InvalidCharacter(c) => write!(f, "'{}' is not a hex digit", c),
+        }
+    }
+}
+
+impl std::error::Error for ObjectIdError {}
+
+fn process_random() -> [u8; 5] {
+    static RANDOM: OnceLock<[u8; 5]> = OnceLock::new();
+    *RANDOM.get_or_init(rand::random)
+}
+
+fn next_count() -> u32 {
+    static COUNTER: OnceLock<AtomicU32> = OnceLock::new();
+    COUNTER.get_or_init(|| AtomicU32::new(rand::random())).fetch_add(1, Ordering::Relaxed) & 0xff_ffff
+}
+
+impl ObjectId {
+    // a new id , made now
+    #[allow(clippy::new_without_default)] // no Default on purpose , a default id that is different every time would be a surprise
+    pub fn new() -> ObjectId {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        ObjectId::from_parts(seconds as u32, process_random(), next_count())
+    }
+
+    // only the low 3 bytes of the counter are used
+    pub fn from_parts(timestamp: u32, random: [u8; 5], counter: u32) -> ObjectId {
+        let mut bytes = [0; 12];
+        bytes[..4].copy_from_slice(&timestamp.to_be_bytes());
+        bytes[4..9].copy_from_slice(&random);
+        bytes[9..].copy_from_slice(&counter.to_be_bytes()[1..]);
+        ObjectId(bytes)
+    }
+
+    pub fn from_bytes(bytes: [u8; 12]) -> ObjectId {
+        ObjectId(bytes)
+    }
+
+    pub fn bytes(&self) -> [u8; 12] {
+        self.0
+    }
+
+    // seconds since 1970
+    pub fn timestamp(&self) -> u32 {
+        u32::from_be_bytes([self.0[0], self.0[1], self.0[2], self.0[3]])
+    }
+
+    // when the id was made
+    pub fn created(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.timestamp() as u64)
+    }
+}
+
+// upper case hex digits are accepted , ids are always written in lower case
+impl FromStr for ObjectId {
+    type Err = ObjectIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ObjectIdError::InvalidCharacter(c));
+        }
+        if s.len() != 24 {
+            return Err(ObjectIdError::Length(s.len()));
+        }
+        let mut bytes = [0; 12];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| ObjectIdError::Length(s.len()))?;
+        }
+        Ok(ObjectId(bytes))
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+// in JSON an ObjectId is its 24 hex digits , "630c2272eabd3d30fe44d139"
+impl Serialize for ObjectId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e| de::Error::custom(format!("invalid ObjectId '{}' : {}", s, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let id: ObjectId = "630c2272eabd3d30fe44d139".parse().unwrap();
+        assert_eq!(id.to_string(), "630c2272eabd3d30fe44d139");
+        assert_eq!(id.timestamp(), 0x630c2272);
+        assert_eq!(id.created(), UNIX_EPOCH + Duration::from_secs(1_661_739_634));
+        assert_eq!("630C2272EABD3D30FE44D139".parse::<ObjectId>(), Ok(id));
+
+        assert_eq!("630c2272".parse::<ObjectId>(), Err(ObjectIdError::Length(8)));
+        assert_eq!("630c2272eabd3d30fe44d13g".parse::<ObjectId>(), Err(ObjectIdError::InvalidCharacter('g')));
+        assert_eq!("630c2272eabd3d30fe44d13é".parse::<ObjectId>(), Err(ObjectIdError::InvalidCharacter('é')));
+    }
+
+    #[test]
+    fn test_new_and_order() {
+        let a = ObjectId::new();
+        let b = ObjectId::new();
+        assert_ne!(a, b);
+        assert_eq!(a.bytes()[4..9], b.bytes()[4..9]);
+
+        let old = ObjectId::from_parts(0x630c2272, [0xff; 5], 0xffffff);
+        let new = ObjectId::from_parts(0x630c2273, [0; 5], 0);
+        assert!(old < new);
+        assert!(old < a);
+        assert_eq!(old.to_string(), "630c2272ffffffffffffffff");
+    }
+
+    #[test]
+    fn test_serde() {
+        let id: ObjectId = "630c2272eabd3d30fe44d139".parse().unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"630c2272eabd3d30fe44d139\"");
+        assert_eq!(serde_json::from_str::<ObjectId>("\"630c2272eabd3d30fe44d139\"").unwrap(), id);
+
+        let e = serde_json::from_str::<ObjectId>("\"xyz\"").unwrap_err();
+        assert!(e.to_string().contains("invalid ObjectId 'xyz'"));
+    }
+}
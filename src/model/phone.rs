@@ -0,0 +1,245 @@
+use std::fmt;
+
+/*
+    PhoneNumber -> a phone number split into country code and national number,
+    printed (Display) in E.164 form : "+" country code national number , digits only
+
+    "+1 (880) 516-2365" -> country_code 1 , national "8805162365" -> "+18805162365"
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PhoneNumber {
+    pub country_code: u16,
+    pub national: String,
+}
+
+// E.164 allows at most 15 digits , country code included
+pub const MAX_DIGITS: usize = 15;
+pub const MIN_NATIONAL_DIGITS: usize = 4;
+
+// North American Numbering Plan (US, Canada and the caribbean) , country code 1
+pub const NANP: u16 = 1;
+
+/*
+    the assigned country calling codes (ITU-T E.164).
+    no code is a prefix of another one , so the first match while reading digits is the country code.
+*/
+const COUNTRY_CODES: &[u16] = &[
+    1, 7, 20, 27, 30, 31, 32, 33, 34, 36, 39, 40, 41, 43, 44, 45, 46, 47, 48, 49,
+    51, 52, 53, 54, 55, 56, 57, 58, 60, 61, 62, 63, 64, 65, 66, 81, 82, 84, 86,
+    90, 91, 92, 93, 94, 95, 98,
+    211, 212, 213, 216, 218, 220, 221, 222, 223, 224, 225, 226, 227, 228, 229,
+    230, 231, 232, 233, 234, 235, 236, 237, 238, 239, 240, 241, 242, 243, 244,
+    245, 246, 248, 249, 250, 251, 252, 253, 254, 255, 256, 257, 258, 260, 261,
+    262, 263, 264, 265, 266, 267, 268, 269, 290, 291, 297, 298, 299,
+    350, 351, 352, 353, 354, 355, 356, 357, 358, 359, 370, 371, 372, 373, 374,
+    375, 376, 377, 378, 379, 380, 381, 382, 383, 385, 386, 387, 389,
+    420, 421, 423, 500, 501, 502, 503, 504, 505, 506, 507, 508, 509,
+    590, 591, 592, 593, 594, 595, 596, 597, 598, 599,
+    670, 672, 673, 674, 675, 676, 677, 678, 679, 680, 681, 682, 683, 685, 686,
+    687, 688, 689, 690, 691, 692, 850, 852, 853, 855, 856, 870, 880, 886,
+    960, 961, 962, 963, 964, 965, 966, 967, 968, 970, 971, 972, 973, 974, 975,
+    976, 977, 992, 993, 994, 995, 996, 998,
+];
+
+pub fn is_country_code(cc: u16) -> bool {
+    COUNTRY_CODES.contains(&cc)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhoneError {
+    Empty,
+    InvalidCharacter(char),
+    // no leading '+' (or 00) , and no default country code was given
+    NoCountryCode,
+    UnknownCountryCode(String),
+    TooShort(usize),
+    TooLong(usize),
+    // a NANP number must be area code + exchange + subscriber , 3 + 3 + 4 digits
+    InvalidNanp(String),
+}
+
+impl fmt::Display for PhoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhoneError::Empty => write!(f, "is empty"),
+            PhoneError::InvalidCharacter(c) => write!(f, "'{}' is not allowed in a phone number", c),
+            PhoneError::NoCountryCode => write!(f, "has no country code (start it with '+')"),
+            PhoneError::UnknownCountryCode(digits) => write!(f, "+{} does not start with a known country code", digits),
+            PhoneError::TooShort(n) => write!(f, "national number has {} digits , expected at least {}", n, MIN_NATIONAL_DIGITS),
+            PhoneError::TooLong(n) => write!(f, "has {} digits , E.164 allows at most {}", n, MAX_DIGITS),
+            PhoneError::InvalidNanp(reason) => write!(f, "not a valid North American number : {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PhoneError {}
+
+impl PhoneNumber {
+    /*
+        parse a phone number written in (almost) any style :
+        digits, spaces, dots, dashes and parentheses are accepted.
+
+        "+1 (880) 516-2365" , "001 880 516 2365" -> the country code is in the number
+        "(880) 516-2365"                         -> needs default_country , Some(1) here
+    */
+    pub fn parse(input: &str, default_country: Option<u16>) -> Result<PhoneNumber, PhoneError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(PhoneError::Empty);
+        }
+
+        let mut digits = String::new();
+        for (i, c) in input.char_indices() {
+            match c {
+                '0'..='9' => digits.push(c),
+                '+' if i == 0 => {}
+                ' ' | '-' | '.' | '(' | ')' => {}
+                other => return Err(PhoneError::InvalidCharacter(other)),
+            }
+        }
+
+        let international = if input.starts_with('+') {
+            Some(digits.as_str())
+        } else {
+            digits.strip_prefix("00")
+        };
+
+        let number = match (international, default_country) {
+            (Some(digits), _) => {
+                let (cc, national) = split_country_code(digits)?;
+                PhoneNumber { country_code: cc, national: national.to_string() }
+            }
+            (None, Some(cc)) => {
+                // a NANP number may be written with its leading 1 : "1 880 516 2365"
+                let national = match cc {
+                    NANP if digits.len() == 11 && digits.starts_with('1') => &digits[1..],
+                    NANP => digits.as_str(),
+                    // other countries dial a trunk '0' in front of national numbers
+                    _ => digits.trim_start_matches('0'),
+                };
+                PhoneNumber { country_code: cc, national: national.to_string() }
+            }
+            (None, None) => return Err(PhoneError::NoCountryCode),
+        };
+
+        number.check()?;
+        Ok(number)
+    }
+
+    fn check(&self) -> Result<(), PhoneError> {
+        let total = self.country_code.to_string().len() + self.national.len();
+        if total > MAX_DIGITS {
+            return Err(PhoneError::TooLong(total));
+        }
+        if self.national.len() < MIN_NATIONAL_DIGITS {
+            return Err(PhoneError::TooShort(self.national.len()));
+        }
+        if self.country_code == NANP {
+            let d = self.national.as_bytes();
+            if d.len() != 10 {
+                return Err(PhoneError::InvalidNanp(format!("{} digits , expected 10", d.len())));
+            }
+            if d[0] < b'2' {
+                return Err(PhoneError::InvalidNanp(format!("area code {} starts with {}", &self.national[..3], d[0] as char)));
+            }
+            if d[3] < b'2' {
+                return Err(PhoneError::InvalidNanp(format!("exchange {} starts with {}", &self.national[3..6], d[3] as char)));
+            }
+        }
+        Ok(())
+    }
+
+    // +18805162365
+    pub fn e164(&self) -> String {
+        format!("+{}{}", self.country_code, self.national)
+    }
+
+    /*
+        national style :
+        NANP           -> (880) 516-2365
+        anything else  -> the national number in groups of 3 digits, the last group takes what is left
+    */
+    pub fn format_national(&self) -> String {
+        if self.country_code == NANP && self.national.len() == 10 {
+            return format!("({}) {}-{}", &self.national[..3], &self.national[3..6], &self.national[6..]);
+        }
+        let mut groups: Vec<&str> = Vec::new();
+        let mut rest = self.national.as_str();
+        while rest.len() > 4 {
+            let (head, tail) = rest.split_at(3);
+            groups.push(head);
+            rest = tail;
+        }
+        groups.push(rest);
+        groups.join(" ")
+    }
+
+    // +1 (880) 516-2365
+    pub fn format_international(&self) -> String {
+        format!("+{} {}", self.country_code, self.format_national())
+    }
+}
+
+fn split_country_code(digits: &str) -> Result<(u16, &str), PhoneError> {
+    // no country code starts with 0
+    if digits.starts_with('0') {
+        return Err(PhoneError::UnknownCountryCode(digits.to_string()));
+    }
+    for len in 1..=3 {
+        if digits.len() <= len {
+            break;
+        }
+        let cc: u16 = digits[..len].parse().unwrap_or(0);
+        if COUNTRY_CODES.contains(&cc) {
+            return Ok((cc, &digits[len..]));
+        }
+    }
+    Err(PhoneError::UnknownCountryCode(digits.to_string()))
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.e164())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nanp() {
+        let p = PhoneNumber::parse("+1 (880) 516-2365", None).unwrap();
+        assert_eq!(p.country_code, 1);
+        assert_eq!(p.e164(), "+18805162365");
+        assert_eq!(p.format_national(), "(880) 516-2365");
+        assert_eq!(p.format_international(), "+1 (880) 516-2365");
+
+        for other in ["+18805162365", "001 880.516.2365", "1-880-516-2365", "(880) 516 2365"] {
+            assert_eq!(PhoneNumber::parse(other, Some(NANP)).unwrap(), p, "{}", other);
+        }
+    }
+
+    #[test]
+    fn test_parse_other_countries() {
+        let uk = PhoneNumber::parse("+44 20 7946 0958", None).unwrap();
+        assert_eq!((uk.country_code, uk.national.as_str()), (44, "2079460958"));
+        assert_eq!(PhoneNumber::parse("020 7946 0958", Some(44)).unwrap(), uk);
+        assert_eq!(uk.format_national(), "207 946 0958");
+
+        let ireland = PhoneNumber::parse("+353 1 234 5678", None).unwrap();
+        assert_eq!(ireland.country_code, 353);
+    }
+
+    #[test]
+    fn test_impossible_numbers() {
+        assert_eq!(PhoneNumber::parse("", None), Err(PhoneError::Empty));
+        assert_eq!(PhoneNumber::parse("+1 880 CALL", None), Err(PhoneError::InvalidCharacter('C')));
+        assert_eq!(PhoneNumber::parse("880 516 2365", None), Err(PhoneError::NoCountryCode));
+        assert!(matches!(PhoneNumber::parse("+0 123 4567", None), Err(PhoneError::UnknownCountryCode(_))));
+        assert!(matches!(PhoneNumber::parse("+1 (180) 516-2365", None), Err(PhoneError::InvalidNanp(_))));
+        assert!(matches!(PhoneNumber::parse("+1 516-2365", None), Err(PhoneError::InvalidNanp(_))));
+        assert_eq!(PhoneNumber::parse("+44 123", None), Err(PhoneError::TooShort(3)));
+        assert_eq!(PhoneNumber::parse("+44 1234 5678 9012 34", None), Err(PhoneError::TooLong(16)));
+    }
+}
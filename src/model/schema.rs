@@ -0,0 +1,352 @@
+use serde::de::Error as _;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::{Customer, ObjectId};
+
+/*
+    versions of the customer record layout.
+
+    a record with a "version" key is of that version , a record without one is version 1 ,
+    the layout from before versions were added. when a record is read it is first migrated ,
+    one version at a time , up to SCHEMA_VERSION and only then turned into a Customer.
+
+        version 1 -> the name could be split in "first_name" / "last_name"
+                     and the age could be a string
+        version 2 -> "name" and a numeric "age" , or no age at all
+
+    records are written without a "version" key , so the output is the same as before versions :
+    a version 2 record read back as version 1 goes through v1_to_v2 unchanged.
+    the first migration that would change a version 2 record has to start writing the key.
+
+    a new version = a new entry in MIGRATIONS , from the version before it.
+    keys that are not Customer fields are kept in Customer::extra and written back unchanged.
+*/
+
+pub const SCHEMA_VERSION: u64 = 2;
+
+// changes a record of one version into a record of the next version
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+// (from version , migration to the next version)
+const MIGRATIONS: &[(u64, Migration)] = &[(1, v1_to_v2)];
+
+fn v1_to_v2(record: &mut Map<String, Value>) -> Result<(), String> {
+    let part = |key: &str| record.get(key).and_then(Value::as_str).unwrap_or("").trim().to_string();
+    let name = format!("{} {}", part("first_name"), part("last_name")).trim().to_string();
+    // the split name is gone in version 2 , even when the record has a "name" too
+    record.remove("first_name");
+    record.remove("last_name");
+    if !name.is_empty() && !record.contains_key("name") {
+        record.insert(String::from("name"), Value::String(name));
+    }
+
+    if let Some(Value::String(age)) = record.get("age") {
+        if age.trim().is_empty() {
+            // "" was how version 1 wrote an unknown age
+            record.remove("age");
+        } else {
+            let age: u32 = age.trim().parse().map_err(|_| format!("age '{}' is not a number", age))?;
+            record.insert(String::from("age"), Value::from(age));
+        }
+    }
+    Ok(())
+}
+
+// bring a record up to SCHEMA_VERSION , returns the version it had
+pub fn migrate(record: &mut Map<String, Value>) -> Result<u64, String> {
+    let from = match record.remove("version") {
+        None => 1,
+        Some(v) => v.as_u64().ok_or_else(|| format!("version {} is not a number", v))?,
+    };
+    if from == 0 || from > SCHEMA_VERSION {
+        return Err(format!("record version {} is not supported , the latest is {}", from, SCHEMA_VERSION));
+    }
+
+    for &(version, step) in MIGRATIONS {
+        if version >= from {
+            step(record).map_err(|e| format!("migrating from version {} : {}", version, e))?;
+        }
+    }
+    Ok(from)
+}
+
+/*
+    the keys of a record at SCHEMA_VERSION , one per Customer field , in the order of Customer::FIELDS :
+
+        aliases  -> the names the field had in older exports , read as the field
+        required -> customerid and name , every other field gets a default when it is missing
+        nullable -> age , null is the same as no age
+
+    the deserializer below and the JSON Schema of customers::jsonschema are both made from this table
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordField {
+    CustomerId,
+    Age,
+    EyeColor,
+    Name,
+    Gender,
+    Company,
+    Email,
+    Phone,
+    Address,
+}
+
+impl RecordField {
+    pub const ALL: [RecordField; 9] = [
+        RecordField::CustomerId,
+        RecordField::Age,
+        RecordField::EyeColor,
+        RecordField::Name,
+        RecordField::Gender,
+        RecordField::Company,
+        RecordField::Email,
+        RecordField::Phone,
+        RecordField::Address,
+    ];
+
+    pub fn parse(s: &str) -> Option<RecordField> {
+        RecordField::ALL.into_iter().find(|f| f.name() == s)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RecordField::CustomerId => "customerid",
+            RecordField::Age => "age",
+            RecordField::EyeColor => "eyecolor",
+            RecordField::Name => "name",
+            RecordField::Gender => "gender",
+            RecordField::Company => "company",
+            RecordField::Email => "email",
+            RecordField::Phone => "phone",
+            RecordField::Address => "address",
+        }
+    }
+
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            RecordField::CustomerId => &["customerId", "customer_id"],
+            RecordField::EyeColor => &["eyeColor", "eye_color"],
+            RecordField::Email => &["emailAddress"],
+            RecordField::Phone => &["phoneNumber", "phone_number"],
+            _ => &[],
+        }
+    }
+
+    pub fn required(self) -> bool {
+        matches!(self, RecordField::CustomerId | RecordField::Name)
+    }
+
+    pub fn nullable(self) -> bool {
+        self == RecordField::Age
+    }
+}
+
+// the field under its own name , when the record only has it under an alias
+fn rename_aliases(record: &mut Map<String, Value>) {
+    for field in RecordField::ALL {
+        if record.contains_key(field.name()) {
+            continue;
+        }
+        if let Some(value) = field.aliases().iter().find_map(|alias| record.remove(*alias)) {
+            record.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+// what serde reads after migrate() and rename_aliases() , the defaults are the ones of RecordField
+#[derive(Deserialize)]
+struct Record {
+    customerid: ObjectId,
+    #[serde(default)]
+    age: Option<u32>,
+    #[serde(default)]
+    eyecolor: String,
+    name: String,
+    #[serde(default)]
+    gender: String,
+    #[serde(default)]
+    company: String,
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    phone: String,
+    #[serde(default)]
+    address: String,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl<'de> Deserialize<'de> for Customer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut record = Map::deserialize(deserializer)?;
+        migrate(&mut record).map_err(D::Error::custom)?;
+        rename_aliases(&mut record);
+        let r = Record::deserialize(Value::Object(record)).map_err(D::Error::custom)?;
+        Ok(Customer {
+            customerid: r.customerid,
+            age: r.age,
+            eyecolor: r.eyecolor,
+            name: r.name,
+            gender: r.gender,
+            company: r.company,
+            email: r.email,
+            phone: r.phone,
+            address: r.address,
+            extra: r.extra,
+        })
+    }
+}
+
+impl Customer {
+    // the extra keys that are written , a key that is also a field would be written twice
+    fn extra_entries(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.extra.iter().filter(|(key, _)| key.as_str() != "version" && !Customer::FIELDS.contains(&key.as_str()))
+    }
+
+    // the number of keys of a written record
+    fn entry_count(&self) -> usize {
+        Customer::FIELDS.len() - self.age.is_none() as usize + self.extra_entries().count()
+    }
+}
+
+// the nine fields in the order of Customer::FIELDS , then the extra keys ; no age when there is none
+impl Serialize for Customer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // the exact number of entries , serializers that write the length first need it
+        let mut map = serializer.serialize_map(Some(self.entry_count()))?;
+        map.serialize_entry("customerid", &self.customerid)?;
+        if let Some(age) = self.age {
+            map.serialize_entry("age", &age)?;
+        }
+        map.serialize_entry("eyecolor", &self.eyecolor)?;
+        map.serialize_entry("name", &self.name)?;
+        map.serialize_entry("gender", &self.gender)?;
+        map.serialize_entry("company", &self.company)?;
+        map.serialize_entry("email", &self.email)?;
+        map.serialize_entry("phone", &self.phone)?;
+        map.serialize_entry("address", &self.address)?;
+        for (key, value) in self.extra_entries() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_defaults_and_aliases() {
+        let c: Customer = serde_json::from_value(json!({
+            "customerId": "630c2272eabd3d30fe44d139",
+            "name": "Mabel Haley",
+            "eyeColor": "brown",
+            "phone_number": "+1 (880) 516-2365",
+        }))
+        .unwrap();
+        assert_eq!(c.customerid.to_string(), "630c2272eabd3d30fe44d139");
+        assert_eq!((c.eyecolor.as_str(), c.phone.as_str()), ("brown", "+1 (880) 516-2365"));
+        assert_eq!((c.age, c.email.as_str()), (None, ""));
+        assert!(c.extra.is_empty());
+        let json = serde_json::to_value(&c).unwrap();
+        assert!(json.get("age").is_none() && json.get("version").is_none(), "{}", json);
+        assert_eq!(c.entry_count(), json.as_object().unwrap().len());
+
+        let e = serde_json::from_value::<Customer>(json!({ "customerid": "630c2272eabd3d30fe44d139" })).unwrap_err();
+        assert!(e.to_string().contains("missing field `name`"), "{}", e);
+    }
+
+    // the table is the fields of Customer , and what it says is what the deserializer does
+    #[test]
+    fn test_record_fields() {
+        assert_eq!(RecordField::ALL.map(RecordField::name), Customer::FIELDS);
+        assert_eq!(RecordField::parse("eyecolor"), Some(RecordField::EyeColor));
+
+        let mut record = json!({ "customerid": "630c2272eabd3d30fe44d139", "name": "Mabel Haley" });
+        for field in RecordField::ALL.into_iter().filter(|f| !f.required()) {
+            assert!(serde_json::from_value::<Customer>(record.clone()).is_ok(), "{} can be missing", field.name());
+        }
+        for field in RecordField::ALL.into_iter().filter(|f| f.required()) {
+            let mut missing = record.clone();
+            missing.as_object_mut().unwrap().remove(field.name());
+            assert!(serde_json::from_value::<Customer>(missing).is_err(), "{} is required", field.name());
+        }
+        for field in RecordField::ALL {
+            let null = serde_json::from_value::<Customer>(json!({ "customerid": "630c2272eabd3d30fe44d139", "name": "x", field.name(): null }));
+            assert_eq!(null.is_ok(), field.nullable(), "{} null", field.name());
+        }
+        record["emailAddress"] = json!("a@b.com");
+        record["email"] = json!("c@d.com");
+        let c: Customer = serde_json::from_value(record).unwrap();
+        // the field wins over its alias , which is kept as an extra key
+        assert_eq!((c.email.as_str(), c.extra["emailAddress"].as_str()), ("c@d.com", Some("a@b.com")));
+    }
+
+    #[test]
+    fn test_extra_fields_round_trip() {
+        let record = json!({
+            "customerid": "630c2272eabd3d30fe44d139",
+            "age": 28,
+            "eyecolor": "brown",
+            "name": "Mabel Haley",
+            "gender": "female",
+            "company": "ENOMEN",
+            "email": "mabelhaley@enomen.com",
+            "phone": "+1 (880) 516-2365",
+            "address": "184 Bergen Court, Gorham, American Samoa, 8722",
+            "loyalty": { "tier": "gold", "points": 120 },
+            "tags": ["vip"],
+        });
+        let c: Customer = serde_json::from_value(record.clone()).unwrap();
+        assert_eq!(c.extra["loyalty"]["tier"], "gold");
+        assert_eq!(c.extra.len(), 2);
+        // the length given to the serializer is the number of keys written , the ones that clash with a field are not
+        let mut clashing = c.clone();
+        clashing.extra.insert(String::from("name"), Value::from("x"));
+        clashing.extra.insert(String::from("version"), Value::from(7));
+        assert_eq!(clashing.entry_count(), 11);
+        assert_eq!(serde_json::to_value(&clashing).unwrap().as_object().unwrap().len(), 11);
+        assert_eq!(serde_json::to_value(&c).unwrap(), record);
+    }
+
+    #[test]
+    fn test_migration() {
+        let c: Customer = serde_json::from_value(json!({
+            "customerid": "630c2272eabd3d30fe44d139",
+            "first_name": "Mabel",
+            "last_name": "Haley",
+            "age": "28",
+        }))
+        .unwrap();
+        assert_eq!((c.name.as_str(), c.age), ("Mabel Haley", Some(28)));
+        assert!(c.extra.is_empty());
+        assert!(serde_json::to_value(&c).unwrap().get("version").is_none());
+
+        // the split name goes away when there is a name already , a blank age is no age
+        let c: Customer = serde_json::from_value(json!({
+            "customerid": "630c2272eabd3d30fe44d139",
+            "name": "Mabel Haley",
+            "first_name": "Mabel",
+            "last_name": "Haley",
+            "age": " ",
+        }))
+        .unwrap();
+        assert_eq!((c.name.as_str(), c.age), ("Mabel Haley", None));
+        assert!(c.extra.is_empty(), "{:?}", c.extra);
+
+        let mut record = json!({ "version": 2, "age": "28" }).as_object().unwrap().clone();
+        assert_eq!(migrate(&mut record), Ok(2));
+        // version 2 ages are numbers already , the string is left to serde to reject
+        assert_eq!(record["age"], "28");
+
+        let e = serde_json::from_value::<Customer>(json!({ "version": 9, "customerid": "630c2272eabd3d30fe44d139", "name": "x" })).unwrap_err();
+        assert!(e.to_string().contains("record version 9 is not supported"), "{}", e);
+        let e = serde_json::from_value::<Customer>(json!({ "customerid": "630c2272eabd3d30fe44d139", "name": "x", "age": "old" })).unwrap_err();
+        assert!(e.to_string().contains("age 'old' is not a number"), "{}", e);
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+
+use super::client::{HttpClient, Request};
+
+/*
+    batch -> many GET requests at once , one tokio task per URL
+
+        concurrency -> at most that many requests are out at the same moment (a Semaphore) ,
+                       the others wait for a permit
+        timeout     -> the one of the HttpClient , for every request on its own
+        results     -> one FetchResult per URL , in the order of the URLs , whatever order they finish in
+
+    a status of 400 and up is a result like any other , error is only set when there was no response
+    (no connection , timed out , an invalid URL ...).
+*/
+
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FetchResult {
+    pub url: String,
+    pub status: Option<u16>,
+    // from the moment the request got its permit to the end of the body , retries included
+    #[serde(rename = "latency_ms", serialize_with = "as_millis")]
+    pub latency: Duration,
+    pub bytes: u64,
+    pub error: Option<String>,
+}
+
+impl FetchResult {
+    // a response , with a status below 400
+    pub fn is_ok(&self) -> bool {
+        matches!(self.status, Some(status) if status < 400)
+    }
+}
+
+fn as_millis<S: serde::Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u64(d.as_millis() as u64)
+}
+
+// the URLs of a file , one per line ; blank lines and lines starting with '#' are skipped
+pub fn read_urls(text: &str) -> Vec<String> {
+    text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')).map(String::from).collect()
+}
+
+/*
+    GET every URL , at most concurrency at a time (at least 1).
+    each is called with every result , in the order of the URLs , as soon as it and the ones before it are done
+*/
+pub async fn fetch_all<F: FnMut(&FetchResult)>(client: &HttpClient, urls: &[String], concurrency: usize, mut each: F) -> Vec<FetchResult> {
+    let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+    let tasks: Vec<_> = urls
+        .iter()
+        .map(|url| {
+            let (client, permits, url) = (client.clone(), permits.clone(), url.clone());
+            tokio::spawn(async move {
+                // the semaphore is never closed , so a permit always comes
+                let _permit = permits.acquire_owned().await.expect("the semaphore is not closed");
+                fetch(&client, url).await
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (task, url) in tasks.into_iter().zip(urls) {
+        let result = match task.await {
+            Ok(result) => result,
+            // a task that panicked , the other URLs go on
+            Err(e) => FetchResult { url: url.clone(), status: None, latency: Duration::ZERO, bytes: 0, error: Some(e.to_string()) },
+        };
+        each(&result);
+        results.push(result);
+    }
+    results
+}
+
+async fn fetch(client: &HttpClient, url: String) -> FetchResult {
+    let start = Instant::now();
+    // the attempts are not logged , the result says how it went
+    let response = client.send_logged(&Request::get(&url), |_| {}).await;
+    let latency = start.elapsed();
+    match response {
+        Ok(r) => FetchResult { url, status: Some(r.status.as_u16()), latency, bytes: r.body.len() as u64, error: None },
+        Err(e) => FetchResult { url, status: None, latency, bytes: 0, error: Some(e.reason()) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::net::stub::{respond, Stub};
+
+    #[test]
+    fn test_read_urls() {
+        let text = "# status pages\nhttp://a/health\n\n   http://b/  \n#http://c/\n";
+        assert_eq!(read_urls(text), ["http://a/health", "http://b/"]);
+    }
+
+    // the handler blocks its thread , so the test needs more than one
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_fetch_all() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let (now, max) = (in_flight.clone(), most.clone());
+        let stub = Stub::start(move |req, _| {
+            let n = now.fetch_add(1, Ordering::SeqCst) + 1;
+            max.fetch_max(n, Ordering::SeqCst);
+            let response = match req.uri().path() {
+                "/slow" => {
+                    std::thread::sleep(Duration::from_millis(400));
+                    respond(200, "late")
+                }
+                "/missing" => respond(404, "no such page"),
+                path => {
+                    std::thread::sleep(Duration::from_millis(30));
+                    respond(200, path)
+                }
+            };
+            now.fetch_sub(1, Ordering::SeqCst);
+            response
+        });
+        let client = HttpClient::builder().timeout(Duration::from_millis(200)).build().unwrap();
+
+        let mut urls: Vec<String> = (0..8).map(|i| stub.url(&format!("/page/{}", i))).collect();
+        urls.push(stub.url("/missing"));
+        urls.push(stub.url("/slow"));
+        urls.push(String::from("not a url"));
+
+        let mut seen = Vec::new();
+        let results = fetch_all(&client, &urls, 3, |r| seen.push(r.url.clone())).await;
+        assert_eq!(seen, urls);
+        assert!(most.load(Ordering::SeqCst) <= 3, "{} requests at once", most.load(Ordering::SeqCst));
+
+        assert!(results[..8].iter().all(|r| r.is_ok() && r.error.is_none()));
+        assert_eq!((results[0].bytes, results[7].bytes), (7, 7));
+        assert!(results[0].latency >= Duration::from_millis(30));
+        assert_eq!((results[8].status, results[8].bytes, results[8].is_ok()), (Some(404), 12, false));
+        assert_eq!((results[9].status, results[9].error.as_deref()), (None, Some("timed out")));
+        assert!(results[10].error.as_deref().unwrap().starts_with("invalid URL"));
+
+        let json = serde_json::to_value(&results[8]).unwrap();
+        assert_eq!(json["status"], 404);
+        assert!(json["latency_ms"].is_u64() && json["error"].is_null());
+    }
+}
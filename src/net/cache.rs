@@ -0,0 +1,532 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use sha2::{Digest, Sha256};
+
+use super::client::Response;
+
+/*
+    HttpCache -> responses kept on disk , so the same request from a script does not go out every time
+
+        key          -> the method , the URL , and the values of the request headers named by the
+                        Vary header of the response (Accept , Accept-Language ...) ,
+                        so a response for "accept: text/csv" is not given to "accept: application/json"
+        fresh        -> Cache-Control max-age says for how long (minus the Age it already had) ,
+                        a fresh entry is served without asking the server
+        revalidation -> a stale entry is asked for again with If-None-Match (its ETag) and / or
+                        If-Modified-Since (its Last-Modified) ; a 304 means it is still good ,
+                        it is served and fresh again
+        offline      -> the network is not used at all , every entry is served , stale or not ,
+                        and a request that is not in the cache fails (exit code 3 , like no connection)
+
+    only GET and HEAD are cached , and only responses with a status that can be reused (200 , 404 ...).
+    the cache belongs to one user , so a "private" response is kept like any other.
+    Cache-Control no-store is never stored , no-cache is stored but always revalidated , like max-age=0.
+
+    on disk every entry is two files , <id>.json (the url , status , headers ...) and <id>.body ,
+    and <key>.vary lists the Vary headers of a method + URL , to find the id of a request.
+    they are kept in a directory of their own , SUBDIR of the directory given , and clear() only
+    removes the files of entries it can read , so a file that is not the cache's is never touched.
+*/
+
+// the directory of the files , inside the directory given to HttpCache::new
+pub const SUBDIR: &str = "rapp1-http-cache";
+
+// the directory used when none is given : $RAPP1_CACHE_DIR , or rapp1/http in the user's cache directory
+pub fn default_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("RAPP1_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+    let base = match (std::env::var_os("XDG_CACHE_HOME"), std::env::var_os("HOME")) {
+        (Some(dir), _) => PathBuf::from(dir),
+        (None, Some(home)) => Path::new(&home).join(".cache"),
+        (None, None) => PathBuf::from(".cache"),
+    };
+    base.join("rapp1").join("http")
+}
+
+// how a response came out of the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    // fresh , the server was not asked
+    Hit,
+    // stale , served as it is because the cache is offline
+    Stale,
+    // stale , the server said 304 Not Modified
+    Revalidated,
+    // from the server , stored when it can be reused
+    Miss,
+}
+
+impl fmt::Display for CacheStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Stale => "stale",
+            CacheStatus::Revalidated => "revalidated",
+            CacheStatus::Miss => "miss",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// a stored response , the body is in a file of its own
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub method: String,
+    pub url: String,
+    // the headers the response varies on , and what the request sent for them ("" = not sent)
+    pub vary: Vec<(String, String)>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    // when the response was stored , or last revalidated , in seconds since 1970
+    pub stored: u64,
+    pub size: u64,
+    #[serde(skip)]
+    pub body: Vec<u8>,
+}
+
+impl Entry {
+    pub fn new(method: &Method, vary: Vec<(String, String)>, response: &Response, now: SystemTime) -> Entry {
+        Entry {
+            method: method.to_string(),
+            url: response.url.clone(),
+            vary,
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect(),
+            stored: seconds(now),
+            size: response.body.len() as u64,
+            body: response.body.clone(),
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    // for how long the entry is fresh after it was stored
+    pub fn ttl(&self) -> Duration {
+        let control = self.header("cache-control").unwrap_or("");
+        if has_directive(control, "no-cache") {
+            return Duration::ZERO;
+        }
+        let max_age = directive(control, "max-age").and_then(|v| v.parse().ok()).unwrap_or(0);
+        let age = self.header("age").and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+        Duration::from_secs(u64::saturating_sub(max_age, age))
+    }
+
+    pub fn age(&self, now: SystemTime) -> Duration {
+        Duration::from_secs(seconds(now).saturating_sub(self.stored))
+    }
+
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        self.age(now) < self.ttl()
+    }
+
+    // the headers that ask the server whether the entry changed , none when it has no ETag / Last-Modified
+    pub fn validators(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = self.header("etag") {
+            headers.push((String::from("if-none-match"), etag.to_string()));
+        }
+        if let Some(modified) = self.header("last-modified") {
+            headers.push((String::from("if-modified-since"), modified.to_string()));
+        }
+        headers
+    }
+
+    // after a 304 : the headers it sent replace the stored ones , and the entry is fresh again
+    pub fn revalidated(&mut self, headers: &HeaderMap, now: SystemTime) {
+        for (name, value) in headers {
+            if name == "content-length" {
+                continue; // the length of the 304 , not of the stored body
+            }
+            if let Ok(value) = value.to_str() {
+                self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name.as_str()));
+                self.headers.push((name.to_string(), value.to_string()));
+            }
+        }
+        self.stored = seconds(now);
+    }
+
+    pub fn response(&self, status: CacheStatus) -> Response {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                headers.append(name, value);
+            }
+        }
+        Response {
+            url: self.url.clone(),
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: self.body.clone(),
+            cache: Some(status),
+        }
+    }
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+// "no-cache" in "public, no-cache, max-age=60"
+fn has_directive(control: &str, name: &str) -> bool {
+    control.split(',').any(|d| d.trim().eq_ignore_ascii_case(name))
+}
+
+// "60" for max-age in "public, max-age=60"
+fn directive<'a>(control: &'a str, name: &str) -> Option<&'a str> {
+    control.split(',').find_map(|d| {
+        let (n, v) = d.trim().split_once('=')?;
+        n.trim().eq_ignore_ascii_case(name).then(|| v.trim().trim_matches('"'))
+    })
+}
+
+pub fn is_cacheable_method(method: &Method) -> bool {
+    *method == Method::GET || *method == Method::HEAD
+}
+
+// a response that can be reused : a status that does not depend on the moment , and no "no-store"
+pub fn is_cacheable(response: &Response) -> bool {
+    let control = response.header("cache-control").unwrap_or("");
+    matches!(response.status.as_u16(), 200 | 203 | 300 | 301 | 404 | 410)
+        && !has_directive(control, "no-store")
+        && vary_names(&response.headers).is_some()
+}
+
+// the lower case names in the Vary header , sorted ; None for "Vary: *" , which can not be matched
+pub fn vary_names(headers: &HeaderMap) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    for value in headers.get_all("vary") {
+        for name in value.to_str().unwrap_or("").split(',').map(|n| n.trim().to_ascii_lowercase()) {
+            if name == "*" {
+                return None;
+            }
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names.sort();
+    Some(names)
+}
+
+fn remove_if_there(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn hash(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize()[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    offline: bool,
+}
+
+impl HttpCache {
+    // the files go to <dir>/SUBDIR , made when the first entry is stored
+    pub fn new(dir: impl Into<PathBuf>) -> HttpCache {
+        HttpCache { dir: dir.into().join(SUBDIR), offline: false }
+    }
+
+    pub fn offline(mut self, offline: bool) -> HttpCache {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    // where the files are , <dir>/SUBDIR
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // the names of the Vary headers of the last response stored for a method + URL
+    fn vary_index(&self, method: &Method, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.vary", hash(&[method.as_str(), url])))
+    }
+
+    fn id(method: &str, url: &str, vary: &[(String, String)]) -> String {
+        let mut parts = vec![method, url];
+        for (name, value) in vary {
+            parts.push(name);
+            parts.push(value);
+        }
+        hash(&parts)
+    }
+
+    /*
+        the entry for a request , header gives the value the request sends for a header name.
+        a file that can not be read , or does not match the request , is no entry
+    */
+    pub fn lookup<H: Fn(&str) -> Option<String>>(&self, method: &Method, url: &str, header: H) -> Option<Entry> {
+        let names: Vec<String> = match fs::read_to_string(self.vary_index(method, url)) {
+            Ok(s) => serde_json::from_str(&s).ok()?,
+            Err(_) => Vec::new(),
+        };
+        let vary: Vec<(String, String)> = names.into_iter().map(|n| (n.clone(), header(&n).unwrap_or_default())).collect();
+        let id = HttpCache::id(method.as_str(), url, &vary);
+
+        let mut entry: Entry = serde_json::from_str(&fs::read_to_string(self.dir.join(format!("{}.json", id))).ok()?).ok()?;
+        if entry.method != method.as_str() || entry.url != url || entry.vary != vary {
+            return None;
+        }
+        entry.body = fs::read(self.dir.join(format!("{}.body", id))).ok()?;
+        (entry.body.len() as u64 == entry.size).then_some(entry)
+    }
+
+    // the body is written before the entry , and both through a rename , so a reader never sees half of one
+    pub fn store(&self, entry: &Entry) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let id = HttpCache::id(&entry.method, &entry.url, &entry.vary);
+        self.write(&format!("{}.body", id), &entry.body)?;
+        self.write(&format!("{}.json", id), serde_json::to_string_pretty(entry)?.as_bytes())?;
+
+        let method = Method::from_bytes(entry.method.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let index = self.vary_index(&method, &entry.url);
+        if entry.vary.is_empty() {
+            remove_if_there(&index)?;
+        } else {
+            let names: Vec<&str> = entry.vary.iter().map(|(n, _)| n.as_str()).collect();
+            self.write(index.file_name().and_then(|n| n.to_str()).unwrap_or_default(), serde_json::to_string(&names)?.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> io::Result<()> {
+        let tmp = self.dir.join(format!("{}.tmp", name));
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, self.dir.join(name))
+    }
+
+    // every entry , without its body , sorted by URL ; no directory is no entries
+    pub fn entries(&self) -> io::Result<Vec<Entry>> {
+        let mut entries: Vec<Entry> = self.entry_files()?.into_iter().map(|(_, entry)| entry).collect();
+        entries.sort_by(|a, b| (&a.url, &a.method).cmp(&(&b.url, &b.method)));
+        Ok(entries)
+    }
+
+    /*
+        the <id>.json files that are entries , with the entry ; a file that does not read as one ,
+        or whose name is not the id of what it holds , is skipped , it is not ours or it is broken
+    */
+    fn entry_files(&self) -> io::Result<Vec<(PathBuf, Entry)>> {
+        let mut entries = Vec::new();
+        for file in self.files()? {
+            if file.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let entry = match serde_json::from_str::<Entry>(&fs::read_to_string(&file)?) {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            let id = HttpCache::id(&entry.method, &entry.url, &entry.vary);
+            if file.file_stem().is_some_and(|stem| *stem == *id) {
+                entries.push((file, entry));
+            }
+        }
+        Ok(entries)
+    }
+
+    // removes every entry , its body and its vary index ; returns the number of entries removed
+    pub fn clear(&self) -> io::Result<usize> {
+        let entries = self.entry_files()?;
+        for (file, entry) in &entries {
+            let id = HttpCache::id(&entry.method, &entry.url, &entry.vary);
+            for name in [format!("{}.body", id), format!("{}.body.tmp", id), format!("{}.json.tmp", id)] {
+                remove_if_there(&self.dir.join(name))?;
+            }
+            if let Ok(method) = Method::from_bytes(entry.method.as_bytes()) {
+                let index = self.vary_index(&method, &entry.url);
+                remove_if_there(&index)?;
+                remove_if_there(&index.with_extension("vary.tmp"))?;
+            }
+            fs::remove_file(file)?;
+        }
+        Ok(entries.len())
+    }
+
+    fn files(&self) -> io::Result<Vec<PathBuf>> {
+        match fs::read_dir(&self.dir) {
+            Ok(dir) => dir.map(|f| f.map(|f| f.path())).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::stub::Stub;
+    use crate::net::{HttpClient, HttpError, Request};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rapp1-cache-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn entry(headers: &[(&str, &str)]) -> Entry {
+        Entry {
+            method: String::from("GET"),
+            url: String::from("http://localhost/x"),
+            vary: Vec::new(),
+            status: 200,
+            headers: headers.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect(),
+            stored: 1_000,
+            size: 2,
+            body: b"ok".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_freshness() {
+        let at = |s: u64| UNIX_EPOCH + Duration::from_secs(s);
+        let e = entry(&[("cache-control", "public, max-age=60")]);
+        assert!(e.is_fresh(at(1_059)) && !e.is_fresh(at(1_060)));
+        // it was already 50 seconds old when it came
+        assert!(!entry(&[("Cache-Control", "max-age=60"), ("age", "50")]).is_fresh(at(1_010)));
+        assert!(!entry(&[("cache-control", "no-cache, max-age=60")]).is_fresh(at(1_000)));
+        assert!(!entry(&[]).is_fresh(at(1_000)));
+
+        let e = entry(&[("etag", "\"v1\""), ("last-modified", "Sun, 06 Nov 1994 08:49:37 GMT")]);
+        assert_eq!(
+            e.validators(),
+            [
+                (String::from("if-none-match"), String::from("\"v1\"")),
+                (String::from("if-modified-since"), String::from("Sun, 06 Nov 1994 08:49:37 GMT"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_lookup_clear() {
+        let cache = HttpCache::new(temp_dir("store"));
+        assert!(cache.entries().unwrap().is_empty());
+
+        let mut json = entry(&[("vary", "Accept")]);
+        json.vary = vec![(String::from("accept"), String::from("application/json"))];
+        let mut csv = json.clone();
+        csv.vary[0].1 = String::from("text/csv");
+        csv.body = b"a,b".to_vec();
+        csv.size = 3;
+        cache.store(&json).unwrap();
+        cache.store(&csv).unwrap();
+
+        let accept = |value: &'static str| move |name: &str| (name == "accept").then(|| value.to_string());
+        assert_eq!(cache.lookup(&Method::GET, &json.url, accept("text/csv")).unwrap().body, b"a,b");
+        assert_eq!(cache.lookup(&Method::GET, &json.url, accept("application/json")).unwrap(), json);
+        assert_eq!(cache.lookup(&Method::GET, &json.url, accept("text/html")), None);
+        assert_eq!(cache.lookup(&Method::HEAD, &json.url, accept("text/csv")), None);
+
+        // a body that was cut short is no entry
+        let id = HttpCache::id("GET", &csv.url, &csv.vary);
+        fs::write(cache.dir().join(format!("{}.body", id)), "a").unwrap();
+        assert_eq!(cache.lookup(&Method::GET, &json.url, accept("text/csv")), None);
+
+        assert_eq!(cache.entries().unwrap().len(), 2);
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert!(fs::read_dir(cache.dir()).unwrap().next().is_none());
+    }
+
+    // files that are not the cache's , next to it or even in its own directory , are left alone
+    #[test]
+    fn test_clear_keeps_other_files() {
+        let dir = temp_dir("other");
+        let cache = HttpCache::new(&dir);
+        cache.store(&entry(&[])).unwrap();
+        assert_eq!(cache.dir(), dir.join(SUBDIR));
+
+        fs::write(dir.join("customers.json"), "[]").unwrap();
+        fs::write(cache.dir().join("package.json"), r#"{"name": "x"}"#).unwrap();
+        let mut copied = entry(&[]);
+        copied.url = String::from("http://localhost/y");
+        fs::write(cache.dir().join("copy.json"), serde_json::to_string(&copied).unwrap()).unwrap();
+
+        assert_eq!(cache.entries().unwrap().len(), 1);
+        assert_eq!(cache.clear().unwrap(), 1);
+        let mut left: Vec<_> = fs::read_dir(cache.dir()).unwrap().map(|f| f.unwrap().file_name().into_string().unwrap()).collect();
+        left.sort();
+        assert_eq!(left, ["copy.json", "package.json"]);
+        assert!(dir.join("customers.json").exists());
+    }
+
+    // a server with one resource , /doc , that changes when the test says so
+    #[tokio::test]
+    async fn test_client_with_cache() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        let version = Arc::new(AtomicU32::new(1));
+        let v = version.clone();
+        let stub = Stub::start(move |req, _| {
+            let etag = format!("\"v{}\"", v.load(Ordering::SeqCst));
+            let builder = hyper::Response::builder().header("etag", &etag).header("cache-control", "max-age=0");
+            match req.uri().path() {
+                "/doc" if req.headers().get("if-none-match").is_some_and(|v| *v == *etag) => builder.status(304).body(hyper::Body::empty()),
+                "/doc" => builder.status(200).body(hyper::Body::from(format!("doc {}", etag))),
+                "/fresh" => hyper::Response::builder().header("cache-control", "max-age=3600").body(hyper::Body::from("fresh")),
+                _ => hyper::Response::builder().status(500).header("cache-control", "no-store").body(hyper::Body::from("down")),
+            }
+            .unwrap()
+        });
+        let cache = HttpCache::new(temp_dir("client"));
+        let client = HttpClient::builder().base_url(&stub.url("/")).cache(cache.clone()).build().unwrap();
+        let get = |path: &'static str| {
+            let client = client.clone();
+            async move { client.send(&Request::get(path)).await.unwrap() }
+        };
+
+        // stored , then asked for again with its ETag and still good
+        let first = get("doc").await;
+        assert_eq!((first.text().as_str(), first.cache), ("doc \"v1\"", Some(CacheStatus::Miss)));
+        let again = get("doc").await;
+        assert_eq!((again.status.as_u16(), again.text().as_str(), again.cache), (200, "doc \"v1\"", Some(CacheStatus::Revalidated)));
+
+        // it changed , the new version replaces the stored one
+        version.store(2, Ordering::SeqCst);
+        assert_eq!(get("doc").await.cache, Some(CacheStatus::Miss));
+        assert_eq!(get("doc").await.text(), "doc \"v2\"");
+        assert_eq!(stub.hits(), 4);
+
+        // fresh for an hour , the server is asked once
+        assert_eq!(get("fresh").await.cache, Some(CacheStatus::Miss));
+        assert_eq!(get("fresh").await.cache, Some(CacheStatus::Hit));
+        assert_eq!(stub.hits(), 5);
+
+        // no-store
+        assert_eq!(get("down").await.status, 500);
+        assert_eq!(cache.entries().unwrap().iter().map(|e| e.url.rsplit('/').next().unwrap()).collect::<Vec<_>>(), ["doc", "fresh"]);
+
+        // offline , the stale entry is served as it is , and what was never stored fails
+        let offline = HttpClient::builder().base_url(&stub.url("/")).cache(cache.clone().offline(true)).build().unwrap();
+        let stale = offline.send(&Request::get("doc")).await.unwrap();
+        assert_eq!((stale.text().as_str(), stale.cache), ("doc \"v2\"", Some(CacheStatus::Stale)));
+        let e = offline.send(&Request::get("down")).await.unwrap_err();
+        assert!(matches!(e, HttpError::Connect { .. }) && e.exit_code() == 3, "{}", e);
+        assert_eq!(stub.hits(), 6);
+    }
+}
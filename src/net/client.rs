@@ -0,0 +1,483 @@
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::{Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use super::cache::{self, CacheStatus, Entry, HttpCache};
+use super::retry::{self, Attempt, RetryPolicy};
+
+/*
+    HttpClient -> one reqwest client , with the settings every request shares :
+
+        base URL        -> requests give a path , "joke/Any" , which is joined to it ;
+                           a full URL ("https://...") is used as it is
+        timeout         -> for the whole request , body included
+        connect timeout -> for the connection only
+        headers         -> sent with every request , on top of the headers of the request
+        retry           -> a RetryPolicy (see net::retry) , none by default
+        cache           -> an HttpCache (see net::cache) for GET and HEAD , none by default
+
+    the client is cheap to clone , the clones share the connection pool.
+    send() returns every response , get() and get_json() turn a status >= 400 into an error.
+*/
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/*
+    the exit code of a failed request , so scripts can tell what went wrong :
+
+        2 -> a bad URL or header , like any other usage error
+        3 -> no connection (DNS , refused , TLS)
+        4 -> timed out
+        5 -> the server answered 4xx
+        6 -> the server answered 5xx
+        7 -> the body is not the JSON that was expected
+        1 -> anything else
+*/
+#[derive(Debug)]
+pub enum HttpError {
+    InvalidUrl(String),
+    InvalidHeader(String),
+    Connect { url: String, message: String },
+    Timeout { url: String },
+    Status { url: String, status: StatusCode },
+    Decode { url: String, message: String },
+    Other { url: String, message: String },
+}
+
+impl HttpError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            HttpError::InvalidUrl(_) | HttpError::InvalidHeader(_) => 2,
+            HttpError::Connect { .. } => 3,
+            HttpError::Timeout { .. } => 4,
+            HttpError::Status { status, .. } if status.is_client_error() => 5,
+            HttpError::Status { .. } => 6,
+            HttpError::Decode { .. } => 7,
+            HttpError::Other { .. } => 1,
+        }
+    }
+
+    // sorts a reqwest error into one of ours
+    fn from_reqwest(url: &str, e: reqwest::Error) -> HttpError {
+        let url = url.to_string();
+        if e.is_timeout() {
+            HttpError::Timeout { url }
+        } else if e.is_connect() {
+            HttpError::Connect { url, message: source_message(&e) }
+        } else {
+            HttpError::Other { url, message: source_message(&e) }
+        }
+    }
+}
+
+// reqwest says "error sending request" , the reason is the last error in the chain of sources
+fn source_message(e: &dyn std::error::Error) -> String {
+    let mut last = e;
+    while let Some(source) = last.source() {
+        last = source;
+    }
+    last.to_string()
+}
+
+impl HttpError {
+    // what went wrong , without the URL
+    pub fn reason(&self) -> String {
+        match self {
+            HttpError::InvalidUrl(s) => format!("invalid URL {}", s),
+            HttpError::InvalidHeader(s) => format!("invalid header {}", s),
+            HttpError::Connect { message, .. } => format!("could not connect : {}", message),
+            HttpError::Timeout { .. } => String::from("timed out"),
+            HttpError::Status { status, .. } => status.to_string(),
+            HttpError::Decode { message, .. } => format!("unexpected body : {}", message),
+            HttpError::Other { message, .. } => message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::InvalidUrl(_) | HttpError::InvalidHeader(_) => write!(f, "{}", self.reason()),
+            HttpError::Connect { url, .. }
+            | HttpError::Timeout { url }
+            | HttpError::Status { url, .. }
+            | HttpError::Decode { url, .. }
+            | HttpError::Other { url, .. } => write!(f, "{} : {}", url, self.reason()),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+// one request , relative to the base URL of the client
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl Request {
+    pub fn new(method: Method, path: &str) -> Request {
+        Request { method, path: path.to_string(), headers: Vec::new(), body: None }
+    }
+
+    pub fn get(path: &str) -> Request {
+        Request::new(Method::GET, path)
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Request {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Request {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+// a response read to the end
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub url: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+    // how the cache answered , None when the client has no cache
+    pub cache: Option<CacheStatus>,
+}
+
+impl Response {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, HttpError> {
+        serde_json::from_slice(&self.body).map_err(|e| HttpError::Decode { url: self.url.clone(), message: e.to_string() })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    // a status of 400 and up is an error
+    pub fn error_for_status(self) -> Result<Response, HttpError> {
+        if self.status.is_client_error() || self.status.is_server_error() {
+            return Err(HttpError::Status { url: self.url, status: self.status });
+        }
+        Ok(self)
+    }
+}
+
+fn header(name: &str, value: &str) -> Result<(HeaderName, HeaderValue), HttpError> {
+    let bad = || HttpError::InvalidHeader(format!("'{}: {}'", name, value));
+    Ok((HeaderName::from_bytes(name.as_bytes()).map_err(|_| bad())?, HeaderValue::from_str(value).map_err(|_| bad())?))
+}
+
+pub struct HttpClientBuilder {
+    base_url: Option<String>,
+    timeout: Duration,
+    connect_timeout: Duration,
+    headers: Vec<(String, String)>,
+    retry: RetryPolicy,
+    cache: Option<HttpCache>,
+}
+
+impl HttpClientBuilder {
+    pub fn base_url(mut self, url: &str) -> HttpClientBuilder {
+        self.base_url = Some(url.to_string());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> HttpClientBuilder {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> HttpClientBuilder {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    // a header for every request , a second one with the same name replaces the first
+    pub fn header(mut self, name: &str, value: &str) -> HttpClientBuilder {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // see net::retry , without a policy every request is sent once
+    pub fn retry(mut self, policy: RetryPolicy) -> HttpClientBuilder {
+        self.retry = policy;
+        self
+    }
+
+    pub fn cache(mut self, cache: HttpCache) -> HttpClientBuilder {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn build(self) -> Result<HttpClient, HttpError> {
+        let base = match self.base_url {
+            None => None,
+            Some(url) => {
+                // without the '/' at the end , joining "joke" to ".../v2" would replace "v2"
+                let url = if url.ends_with('/') { url } else { format!("{}/", url) };
+                Some(Url::parse(&url).map_err(|e| HttpError::InvalidUrl(format!("'{}' : {}", url, e)))?)
+            }
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(concat!("rapp1/", env!("CARGO_PKG_VERSION"))));
+        for (name, value) in &self.headers {
+            let (name, value) = header(name, value)?;
+            headers.insert(name, value);
+        }
+        // kept , the cache needs the values of the headers a response varies on
+        let default_headers = headers.clone();
+
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .default_headers(headers)
+            .build()
+            .map_err(|e| HttpError::Other { url: String::new(), message: source_message(&e) })?;
+        Ok(HttpClient { client, base, retry: self.retry, headers: default_headers, cache: self.cache })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    base: Option<Url>,
+    retry: RetryPolicy,
+    headers: HeaderMap,
+    cache: Option<HttpCache>,
+}
+
+impl HttpClient {
+    pub fn builder() -> HttpClientBuilder {
+        HttpClientBuilder {
+            base_url: None,
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            headers: Vec::new(),
+            retry: RetryPolicy::none(),
+            cache: None,
+        }
+    }
+
+    // the URL a path is sent to
+    pub fn url(&self, path: &str) -> Result<Url, HttpError> {
+        if let Ok(url) = Url::parse(path) {
+            return Ok(url);
+        }
+        match &self.base {
+            Some(base) => base.join(path.trim_start_matches('/')).map_err(|e| HttpError::InvalidUrl(format!("'{}' : {}", path, e))),
+            None => Err(HttpError::InvalidUrl(format!("'{}' : a relative URL , but the client has no base URL", path))),
+        }
+    }
+
+    /*
+        send a request , again and again as the retry policy allows.
+        with retries every attempt is logged to stderr , see send_logged
+    */
+    pub async fn send(&self, request: &Request) -> Result<Response, HttpError> {
+        let quiet = self.retry.attempts == 1;
+        self.send_logged(request, |attempt| {
+            if !quiet {
+                eprintln!("{}", attempt);
+            }
+        })
+        .await
+    }
+
+    /*
+        send , with every attempt given to log ; the last response is returned , whatever its status.
+        with a cache a GET or HEAD is answered from it when it can be (see net::cache) ,
+        then no attempt is made and nothing is logged
+    */
+    pub async fn send_logged<L: FnMut(&Attempt)>(&self, request: &Request, log: L) -> Result<Response, HttpError> {
+        match &self.cache {
+            Some(cache) if cache::is_cacheable_method(&request.method) => self.send_cached(cache, request, log).await,
+            _ => self.send_retrying(request, log).await,
+        }
+    }
+
+    async fn send_cached<L: FnMut(&Attempt)>(&self, cache: &HttpCache, request: &Request, log: L) -> Result<Response, HttpError> {
+        let url = self.url(&request.path)?.to_string();
+        let now = SystemTime::now();
+        let entry = cache.lookup(&request.method, &url, |name| self.request_header(request, name));
+        match &entry {
+            Some(entry) if cache.is_offline() => return Ok(entry.response(CacheStatus::Stale)),
+            Some(entry) if entry.is_fresh(now) => return Ok(entry.response(CacheStatus::Hit)),
+            None if cache.is_offline() => return Err(HttpError::Connect { url, message: String::from("offline , and not in the cache") }),
+            _ => {}
+        }
+
+        let mut sent = request.clone();
+        if let Some(entry) = &entry {
+            sent.headers.extend(entry.validators());
+        }
+        let mut response = self.send_retrying(&sent, log).await?;
+
+        let (entry, status) = match entry {
+            Some(mut entry) if response.status == StatusCode::NOT_MODIFIED => {
+                entry.revalidated(&response.headers, SystemTime::now());
+                response = entry.response(CacheStatus::Revalidated);
+                (Some(entry), CacheStatus::Revalidated)
+            }
+            _ if cache::is_cacheable(&response) => {
+                let names = cache::vary_names(&response.headers).unwrap_or_default();
+                let vary = names.into_iter().map(|n| (n.clone(), self.request_header(request, &n).unwrap_or_default())).collect();
+                (Some(Entry::new(&request.method, vary, &response, SystemTime::now())), CacheStatus::Miss)
+            }
+            _ => (None, CacheStatus::Miss),
+        };
+        if let Some(entry) = entry {
+            // the response is good , a cache that can not be written only costs the next request
+            if let Err(e) = cache.store(&entry) {
+                eprintln!("warning: cache {} : could not store {} : {}", cache.dir().display(), url, e);
+            }
+        }
+        response.cache = Some(status);
+        Ok(response)
+    }
+
+    // the value the request sends for a header , its own or the one of the client
+    fn request_header(&self, request: &Request, name: &str) -> Option<String> {
+        match request.headers.iter().rev().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            Some((_, value)) => Some(value.clone()),
+            None => self.headers.get(name).and_then(|v| v.to_str().ok()).map(String::from),
+        }
+    }
+
+    async fn send_retrying<L: FnMut(&Attempt)>(&self, request: &Request, mut log: L) -> Result<Response, HttpError> {
+        let url = self.url(&request.path)?;
+        let mut number = 0;
+        loop {
+            number += 1;
+            let start = Instant::now();
+            let result = self.send_once(request, &url).await;
+            let outcome = match &result {
+                Ok(r) => Ok((r.status, r.header("retry-after").and_then(|v| retry::parse_retry_after(v, SystemTime::now())))),
+                Err(e) => Err(e),
+            };
+            let retry_in = self.retry.next_delay(number, &request.method, &outcome);
+            log(&Attempt {
+                number,
+                of: self.retry.attempts,
+                method: request.method.clone(),
+                url: url.to_string(),
+                outcome: result.as_ref().map(|r| r.status).map_err(HttpError::reason),
+                elapsed: start.elapsed(),
+                retry_in,
+            });
+            match retry_in {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return result,
+            }
+        }
+    }
+
+    async fn send_once(&self, request: &Request, url: &Url) -> Result<Response, HttpError> {
+        let mut builder = self.client.request(request.method.clone(), url.clone());
+        for (name, value) in &request.headers {
+            let (name, value) = header(name, value)?;
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        let url = url.to_string();
+        let response = builder.send().await.map_err(|e| HttpError::from_reqwest(&url, e))?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await.map_err(|e| HttpError::from_reqwest(&url, e))?;
+        Ok(Response { url, status, headers, body: body.to_vec(), cache: None })
+    }
+
+    // GET , a status of 400 and up is an error
+    pub async fn get(&self, path: &str) -> Result<Response, HttpError> {
+        self.send(&Request::get(path)).await?.error_for_status()
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, HttpError> {
+        self.send(&Request::get(path).header("accept", "application/json")).await?.error_for_status()?.json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::stub::{respond, Stub};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Greeting {
+        message: String,
+    }
+
+    #[test]
+    fn test_urls() {
+        let client = HttpClient::builder().base_url("https://v2.jokeapi.dev/api").build().unwrap();
+        assert_eq!(client.url("joke/Any").unwrap().as_str(), "https://v2.jokeapi.dev/api/joke/Any");
+        assert_eq!(client.url("/joke/Any?amount=2").unwrap().as_str(), "https://v2.jokeapi.dev/api/joke/Any?amount=2");
+        assert_eq!(client.url("http://localhost:1/x").unwrap().as_str(), "http://localhost:1/x");
+
+        let e = HttpClient::builder().build().unwrap().url("joke").unwrap_err();
+        assert_eq!(e.exit_code(), 2);
+        assert!(HttpClient::builder().base_url("not a url").build().is_err());
+        assert!(matches!(HttpClient::builder().header("bad header", "x").build(), Err(HttpError::InvalidHeader(_))));
+    }
+
+    #[tokio::test]
+    async fn test_requests() {
+        let stub = Stub::start(|req, _| match req.uri().path() {
+            "/hello" => {
+                // the default header and the header of the request both arrive
+                let team = req.headers().get("x-team").and_then(|v| v.to_str().ok()).unwrap_or("");
+                let ua = req.headers()["user-agent"].to_str().unwrap().to_string();
+                respond(200, &format!(r#"{{"message": "hello {} from {}"}}"#, team, ua))
+            }
+            "/text" => respond(200, "not json"),
+            "/missing" => respond(404, "{}"),
+            _ => respond(503, "{}"),
+        });
+        let client = HttpClient::builder().base_url(&stub.url("/")).header("x-team", "billing").build().unwrap();
+
+        let greeting: Greeting = client.get_json("hello").await.unwrap();
+        assert_eq!(greeting.message, format!("hello billing from rapp1/{}", env!("CARGO_PKG_VERSION")));
+
+        let e = client.get_json::<Greeting>("text").await.unwrap_err();
+        assert_eq!((e.exit_code(), matches!(e, HttpError::Decode { .. })), (7, true));
+        assert_eq!(client.get("missing").await.unwrap_err().exit_code(), 5);
+        assert_eq!(client.get("down").await.unwrap_err().exit_code(), 6);
+
+        // send() leaves the status to the caller
+        let response = client.send(&Request::get("missing")).await.unwrap();
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(stub.hits(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_and_connect_errors() {
+        let stub = Stub::start(|_, _| {
+            std::thread::sleep(Duration::from_millis(300));
+            respond(200, "{}")
+        });
+        let client = HttpClient::builder().base_url(&stub.url("/")).timeout(Duration::from_millis(50)).build().unwrap();
+        let e = client.get("slow").await.unwrap_err();
+        assert_eq!(e.exit_code(), 4, "{}", e);
+
+        // nothing listens on the port of a listener that was just closed
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let e = HttpClient::builder().build().unwrap().get(&format!("http://127.0.0.1:{}/", port)).await.unwrap_err();
+        assert_eq!(e.exit_code(), 3, "{}", e);
+    }
+}
@@ -0,0 +1,575 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::client::{HttpClient, HttpClientBuilder, HttpError, Request};
+use super::retry::RetryPolicy;
+
+/*
+    JokeClient -> a typed client for JokeAPI (https://v2.jokeapi.dev)
+
+    a JokeQuery says which jokes are wanted :
+
+        categories -> Programming , Misc , Dark , Pun , Spooky , Christmas ; none = Any
+        blacklist  -> flags a joke must not have (nsfw , religious , political , racist , sexist , explicit)
+        type       -> single (one line) or twopart (a setup and a delivery) ; none = both
+        lang       -> cs , de , en , es , fr , pt ; none = en
+        amount     -> 1 to 10 jokes
+        contains   -> a word or phrase the joke must contain
+        safe mode  -> only jokes the API calls safe
+
+    the answer is a Joke , Joke::Single or Joke::TwoPart , each with its JokeInfo (id , category , flags ...).
+    when no joke matches , or the query is wrong , the API answers with an error object ,
+    that is a JokeError::Api with its code and message.
+*/
+
+pub const BASE_URL: &str = "https://v2.jokeapi.dev";
+
+// the most jokes the API gives for one request
+pub const MAX_AMOUNT: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Category {
+    Programming,
+    Misc,
+    Dark,
+    Pun,
+    Spooky,
+    Christmas,
+}
+
+impl Category {
+    pub const ALL: [Category; 6] = [Category::Programming, Category::Misc, Category::Dark, Category::Pun, Category::Spooky, Category::Christmas];
+
+    // case does not matter , like in the API
+    pub fn parse(s: &str) -> Option<Category> {
+        Category::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(s))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Programming => "Programming",
+            Category::Misc => "Misc",
+            Category::Dark => "Dark",
+            Category::Pun => "Pun",
+            Category::Spooky => "Spooky",
+            Category::Christmas => "Christmas",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    Nsfw,
+    Religious,
+    Political,
+    Racist,
+    Sexist,
+    Explicit,
+}
+
+impl Flag {
+    pub const ALL: [Flag; 6] = [Flag::Nsfw, Flag::Religious, Flag::Political, Flag::Racist, Flag::Sexist, Flag::Explicit];
+
+    pub fn parse(s: &str) -> Option<Flag> {
+        Flag::ALL.into_iter().find(|f| f.name().eq_ignore_ascii_case(s))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Flag::Nsfw => "nsfw",
+            Flag::Religious => "religious",
+            Flag::Political => "political",
+            Flag::Racist => "racist",
+            Flag::Sexist => "sexist",
+            Flag::Explicit => "explicit",
+        }
+    }
+}
+
+// the flags of a joke , as the API sends them : { "nsfw": false , "religious": false , ... }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct Flags {
+    pub nsfw: bool,
+    pub religious: bool,
+    pub political: bool,
+    pub racist: bool,
+    pub sexist: bool,
+    pub explicit: bool,
+}
+
+impl Flags {
+    pub fn has(&self, flag: Flag) -> bool {
+        match flag {
+            Flag::Nsfw => self.nsfw,
+            Flag::Religious => self.religious,
+            Flag::Political => self.political,
+            Flag::Racist => self.racist,
+            Flag::Sexist => self.sexist,
+            Flag::Explicit => self.explicit,
+        }
+    }
+
+    // the flags that are set
+    pub fn set(&self) -> Vec<Flag> {
+        Flag::ALL.into_iter().filter(|f| self.has(*f)).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JokeType {
+    Single,
+    TwoPart,
+}
+
+impl JokeType {
+    pub fn parse(s: &str) -> Option<JokeType> {
+        match s.to_ascii_lowercase().as_str() {
+            "single" => Some(JokeType::Single),
+            "twopart" => Some(JokeType::TwoPart),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            JokeType::Single => "single",
+            JokeType::TwoPart => "twopart",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Cs,
+    De,
+    En,
+    Es,
+    Fr,
+    Pt,
+}
+
+impl Language {
+    pub const ALL: [Language; 6] = [Language::Cs, Language::De, Language::En, Language::Es, Language::Fr, Language::Pt];
+
+    pub fn parse(s: &str) -> Option<Language> {
+        Language::ALL.into_iter().find(|l| l.code().eq_ignore_ascii_case(s))
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Cs => "cs",
+            Language::De => "de",
+            Language::En => "en",
+            Language::Es => "es",
+            Language::Fr => "fr",
+            Language::Pt => "pt",
+        }
+    }
+}
+
+// what every joke has , whatever its type
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct JokeInfo {
+    pub id: u32,
+    pub category: Category,
+    pub flags: Flags,
+    pub safe: bool,
+    pub lang: Language,
+}
+
+// the "type" field of the API picks the variant
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type")]
+pub enum Joke {
+    #[serde(rename = "single")]
+    Single {
+        #[serde(flatten)]
+        info: JokeInfo,
+        joke: String,
+    },
+    #[serde(rename = "twopart")]
+    TwoPart {
+        #[serde(flatten)]
+        info: JokeInfo,
+        setup: String,
+        delivery: String,
+    },
+}
+
+impl Joke {
+    pub fn info(&self) -> &JokeInfo {
+        match self {
+            Joke::Single { info, .. } | Joke::TwoPart { info, .. } => info,
+        }
+    }
+
+    pub fn joke_type(&self) -> JokeType {
+        match self {
+            Joke::Single { .. } => JokeType::Single,
+            Joke::TwoPart { .. } => JokeType::TwoPart,
+        }
+    }
+}
+
+// the text of the joke , a two part joke on two lines
+impl fmt::Display for Joke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Joke::Single { joke, .. } => write!(f, "{}", joke),
+            Joke::TwoPart { setup, delivery, .. } => write!(f, "{}\n{}", setup, delivery),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JokeQuery {
+    pub categories: Vec<Category>,
+    pub blacklist: Vec<Flag>,
+    pub joke_type: Option<JokeType>,
+    pub lang: Option<Language>,
+    pub amount: u8,
+    pub contains: Option<String>,
+    pub safe_mode: bool,
+}
+
+impl Default for JokeQuery {
+    fn default() -> Self {
+        JokeQuery { categories: Vec::new(), blacklist: Vec::new(), joke_type: None, lang: None, amount: 1, contains: None, safe_mode: false }
+    }
+}
+
+impl JokeQuery {
+    pub fn new() -> JokeQuery {
+        JokeQuery::default()
+    }
+
+    pub fn category(mut self, category: Category) -> JokeQuery {
+        if !self.categories.contains(&category) {
+            self.categories.push(category);
+        }
+        self
+    }
+
+    pub fn blacklist(mut self, flag: Flag) -> JokeQuery {
+        if !self.blacklist.contains(&flag) {
+            self.blacklist.push(flag);
+        }
+        self
+    }
+
+    pub fn joke_type(mut self, joke_type: JokeType) -> JokeQuery {
+        self.joke_type = Some(joke_type);
+        self
+    }
+
+    pub fn lang(mut self, lang: Language) -> JokeQuery {
+        self.lang = Some(lang);
+        self
+    }
+
+    // 1 to MAX_AMOUNT
+    pub fn amount(mut self, amount: u8) -> JokeQuery {
+        self.amount = amount.clamp(1, MAX_AMOUNT);
+        self
+    }
+
+    pub fn contains(mut self, text: &str) -> JokeQuery {
+        self.contains = Some(text.to_string());
+        self
+    }
+
+    pub fn safe_mode(mut self) -> JokeQuery {
+        self.safe_mode = true;
+        self
+    }
+
+    // the path of the request , "joke/Programming,Pun?blacklistFlags=nsfw&amount=2"
+    pub fn path(&self) -> String {
+        let mut path = String::from("joke/");
+        if self.categories.is_empty() {
+            path.push_str("Any");
+        } else {
+            path.push_str(&self.categories.iter().map(|c| c.name()).collect::<Vec<_>>().join(","));
+        }
+
+        let mut params = Vec::new();
+        if !self.blacklist.is_empty() {
+            params.push(format!("blacklistFlags={}", self.blacklist.iter().map(|f| f.name()).collect::<Vec<_>>().join(",")));
+        }
+        if let Some(t) = self.joke_type {
+            params.push(format!("type={}", t.name()));
+        }
+        if let Some(lang) = self.lang {
+            params.push(format!("lang={}", lang.code()));
+        }
+        if self.amount > 1 {
+            params.push(format!("amount={}", self.amount));
+        }
+        if let Some(text) = &self.contains {
+            // form encoding gives '+' for a space , the API wants %20 ; a real '+' is already %2B
+            let encoded: String = form_urlencoded::byte_serialize(text.as_bytes()).collect();
+            params.push(format!("contains={}", encoded.replace('+', "%20")));
+        }
+        if self.safe_mode {
+            params.push(String::from("safe-mode"));
+        }
+
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        path
+    }
+}
+
+/*
+    a failed joke request :
+
+        Http -> the request itself failed (see HttpError , and its exit codes)
+        Api  -> the API answered with an error object , like code 106 "No matching joke found" ;
+                exit code 5 , like any other request the server refused
+*/
+#[derive(Debug)]
+pub enum JokeError {
+    Http(HttpError),
+    Api { code: u32, message: String, caused_by: Vec<String> },
+}
+
+impl JokeError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            JokeError::Http(e) => e.exit_code(),
+            JokeError::Api { .. } => 5,
+        }
+    }
+}
+
+impl fmt::Display for JokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JokeError::Http(e) => write!(f, "{}", e),
+            JokeError::Api { code, message, caused_by } => {
+                write!(f, "jokeapi error {} : {}", code, message)?;
+                if !caused_by.is_empty() {
+                    write!(f, " ({})", caused_by.join(" , "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for JokeError {}
+
+impl From<HttpError> for JokeError {
+    fn from(e: HttpError) -> Self {
+        JokeError::Http(e)
+    }
+}
+
+// the error object of the API , { "error": true , "code": 106 , "message": "..." , "causedBy": [...] }
+#[derive(Deserialize)]
+struct ApiError {
+    code: u32,
+    message: String,
+    #[serde(rename = "causedBy", default)]
+    caused_by: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JokeClient {
+    http: HttpClient,
+}
+
+impl JokeClient {
+    pub fn new() -> Result<JokeClient, HttpError> {
+        JokeClient::with_base_url(BASE_URL)
+    }
+
+    // another server that speaks the same API , a mirror or a local one for tests
+    pub fn with_base_url(url: &str) -> Result<JokeClient, HttpError> {
+        Ok(JokeClient::from_http(JokeClient::http_builder(url).build()?))
+    }
+
+    // the settings of the client , for more of them (an HttpCache ...) before it is built
+    pub fn http_builder(url: &str) -> HttpClientBuilder {
+        HttpClient::builder()
+            .base_url(url)
+            .timeout(Duration::from_secs(10))
+            .header("accept", "application/json")
+            .retry(RetryPolicy::default())
+    }
+
+    pub fn from_http(http: HttpClient) -> JokeClient {
+        JokeClient { http }
+    }
+
+    // query.amount jokes , or fewer when fewer match
+    pub async fn jokes(&self, query: &JokeQuery) -> Result<Vec<Joke>, JokeError> {
+        let response = self.http.send(&Request::get(&query.path())).await?;
+
+        // the API sends its error object with a 4xx status , anything else that is not JSON is a plain http error
+        let mut body: Value = match response.json() {
+            Ok(body) => body,
+            Err(e) => return Err(response.error_for_status().err().unwrap_or(e).into()),
+        };
+        let decode = |e: serde_json::Error| HttpError::Decode { url: response.url.clone(), message: e.to_string() };
+        if body["error"] == Value::Bool(true) {
+            let e: ApiError = serde_json::from_value(body).map_err(decode)?;
+            return Err(JokeError::Api { code: e.code, message: e.message, caused_by: e.caused_by });
+        }
+        let response = response.clone().error_for_status()?;
+
+        // one joke is the object itself , more come as { "amount": n , "jokes": [...] }
+        let jokes = match body.get_mut("jokes") {
+            Some(jokes) => serde_json::from_value(jokes.take()),
+            None => serde_json::from_value(body).map(|joke| vec![joke]),
+        };
+        jokes.map_err(|e| HttpError::Decode { url: response.url, message: e.to_string() }.into())
+    }
+
+    // one joke , whatever the amount of the query
+    pub async fn joke(&self, query: &JokeQuery) -> Result<Joke, JokeError> {
+        let query = JokeQuery { amount: 1, ..query.clone() };
+        let mut jokes = self.jokes(&query).await?;
+        match jokes.pop() {
+            Some(joke) => Ok(joke),
+            None => Err(JokeError::Api { code: 106, message: String::from("No matching joke found"), caused_by: Vec::new() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::net::stub::{respond, Stub};
+
+    // responses recorded from v2.jokeapi.dev
+    const SINGLE: &str = include_str!("fixtures/jokeapi/single.json");
+    const TWOPART: &str = include_str!("fixtures/jokeapi/twopart.json");
+    const AMOUNT: &str = include_str!("fixtures/jokeapi/amount.json");
+    const NO_MATCH: &str = include_str!("fixtures/jokeapi/no_match.json");
+
+    // a local JokeAPI that answers with the fixtures , and keeps the URIs it was asked for
+    fn fixture_server() -> (Stub, Arc<Mutex<Vec<String>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let stub = Stub::start(move |req, _| {
+            let uri = req.uri().to_string();
+            log.lock().unwrap().push(uri.clone());
+            let query = req.uri().query().unwrap_or("");
+            if !req.uri().path().starts_with("/joke/") {
+                respond(404, "not found")
+            } else if query.contains("contains=") && !query.contains("contains=dark%20mode") {
+                respond(400, NO_MATCH)
+            } else if query.contains("amount=") {
+                respond(200, AMOUNT)
+            } else if query.contains("type=twopart") {
+                respond(200, TWOPART)
+            } else {
+                respond(200, SINGLE)
+            }
+        });
+        (stub, seen)
+    }
+
+    #[test]
+    fn test_query_path() {
+        assert_eq!(JokeQuery::new().path(), "joke/Any");
+        let query = JokeQuery::new()
+            .category(Category::Programming)
+            .category(Category::Pun)
+            .category(Category::Programming)
+            .blacklist(Flag::Nsfw)
+            .blacklist(Flag::Racist)
+            .joke_type(JokeType::TwoPart)
+            .lang(Language::De)
+            .amount(3)
+            .contains("dark mode+")
+            .safe_mode();
+        assert_eq!(
+            query.path(),
+            "joke/Programming,Pun?blacklistFlags=nsfw,racist&type=twopart&lang=de&amount=3&contains=dark%20mode%2B&safe-mode"
+        );
+        assert_eq!(JokeQuery::new().amount(0).amount, 1);
+        assert_eq!(JokeQuery::new().amount(50).amount, MAX_AMOUNT);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Category::parse("programming"), Some(Category::Programming));
+        assert_eq!(Category::parse("Any"), None);
+        assert_eq!(Flag::parse("NSFW"), Some(Flag::Nsfw));
+        assert_eq!(JokeType::parse("TwoPart"), Some(JokeType::TwoPart));
+        assert_eq!(Language::parse("pt"), Some(Language::Pt));
+        assert_eq!(Language::parse("nl"), None);
+    }
+
+    #[test]
+    fn test_decode_fixtures() {
+        let joke: Joke = serde_json::from_str(TWOPART).unwrap();
+        assert_eq!(joke.joke_type(), JokeType::TwoPart);
+        assert_eq!(joke.info().id, 232);
+        assert_eq!(joke.to_string(), "Why do programmers prefer dark mode?\nBecause light attracts bugs.");
+
+        let joke: Joke = serde_json::from_str(SINGLE).unwrap();
+        assert!(matches!(&joke, Joke::Single { joke, info } if joke.contains("UDP") && info.lang == Language::En && info.safe));
+        assert!(joke.info().flags.set().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_client_against_fixtures() {
+        let (stub, seen) = fixture_server();
+        let client = JokeClient::with_base_url(&stub.url("/")).unwrap();
+
+        let joke = client.joke(&JokeQuery::new().category(Category::Programming).safe_mode()).await.unwrap();
+        assert_eq!((joke.joke_type(), joke.info().category), (JokeType::Single, Category::Programming));
+
+        let joke = client.joke(&JokeQuery::new().joke_type(JokeType::TwoPart).contains("dark mode")).await.unwrap();
+        assert!(matches!(joke, Joke::TwoPart { ref delivery, .. } if delivery == "Because light attracts bugs."));
+
+        let jokes = client.jokes(&JokeQuery::new().blacklist(Flag::Nsfw).amount(2)).await.unwrap();
+        assert_eq!(jokes.iter().map(Joke::joke_type).collect::<Vec<_>>(), [JokeType::Single, JokeType::TwoPart]);
+        assert_eq!(jokes[1].info().flags.set(), [Flag::Explicit]);
+        assert!(!jokes[1].info().safe);
+
+        let e = client.jokes(&JokeQuery::new().contains("nothing like this")).await.unwrap_err();
+        assert!(matches!(&e, JokeError::Api { code: 106, .. }), "{}", e);
+        assert_eq!(e.exit_code(), 5);
+        assert!(e.to_string().starts_with("jokeapi error 106 : No matching joke found (No jokes were found"), "{}", e);
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "/joke/Programming?safe-mode",
+                "/joke/Any?type=twopart&contains=dark%20mode",
+                "/joke/Any?blacklistFlags=nsfw&amount=2",
+                "/joke/Any?contains=nothing%20like%20this",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_errors() {
+        let stub = Stub::start(|req, _| match req.uri().path() {
+            "/joke/Any" => respond(200, r#"{"error": false, "type": "limerick", "id": 1}"#),
+            _ => respond(404, "<html>not here</html>"),
+        });
+
+        // a joke of a type this client does not know
+        let client = JokeClient::with_base_url(&stub.url("/")).unwrap();
+        let e = client.joke(&JokeQuery::new()).await.unwrap_err();
+        assert!(matches!(e, JokeError::Http(HttpError::Decode { .. })), "{}", e);
+
+        // not the API at all , the status is the error
+        let client = JokeClient::with_base_url(&stub.url("/v3")).unwrap();
+        let e = client.joke(&JokeQuery::new()).await.unwrap_err();
+        assert!(matches!(e, JokeError::Http(HttpError::Status { .. })), "{}", e);
+        assert_eq!(e.exit_code(), 5);
+    }
+}
@@ -0,0 +1,41 @@
+use std::future::Future;
+use std::sync::OnceLock;
+
+use tokio::runtime::Runtime;
+
+/*
+    net -> http requests
+
+    client -> HttpClient , a reqwest client with a base URL , timeouts , default headers ,
+              JSON decoding and errors that map to exit codes (see HttpError::exit_code)
+    retry  -> RetryPolicy , backoff with jitter and Retry-After for failed requests
+    cache  -> HttpCache , responses on disk , with max-age , ETag / Last-Modified revalidation and an offline mode
+    batch  -> fetch_all , many GET requests on tokio tasks , a limited number at a time
+    joke   -> JokeClient , a typed client for JokeAPI (categories , flags , Joke::Single / Joke::TwoPart)
+
+    everything that is async runs on one tokio runtime , made the first time it is needed :
+    sync code (the sections , the tools) calls net::block_on(...) instead of making its own.
+*/
+
+pub mod batch;
+pub mod cache;
+pub mod client;
+pub mod joke;
+pub mod retry;
+#[cfg(test)]
+pub(crate) mod stub;
+
+pub use self::cache::HttpCache;
+pub use self::client::{HttpClient, HttpClientBuilder, HttpError, Request, Response};
+pub use self::retry::RetryPolicy;
+
+pub fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    // a runtime can only fail to start when the OS is out of threads / file descriptors
+    RUNTIME.get_or_init(|| Runtime::new().expect("could not start the tokio runtime"))
+}
+
+// run a future to the end on the shared runtime , must not be called from inside async code
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use rand::Rng;
+use reqwest::{Method, StatusCode};
+
+use super::client::HttpError;
+
+/*
+    RetryPolicy -> when and how long to wait before a request is sent again
+
+        attempts    -> the most times a request is sent , 1 = no retries
+        backoff     -> the wait doubles after every attempt , base , 2 x base , 4 x base ... up to max_delay ,
+                       with jitter : a random wait between half of that and all of it ,
+                       so clients that failed together do not all come back at the same moment
+        Retry-After -> a 429 or 503 can say how long to wait (seconds , or an HTTP date) ,
+                       that wait is used when it is longer than the backoff ;
+                       when it is longer than max_delay the request is not retried
+
+    only idempotent methods are retried (GET , HEAD , PUT , DELETE , OPTIONS , TRACE) ,
+    sending a POST twice could do the thing twice. they are retried on a 429 , a 5xx ,
+    or when no connection could be made.
+*/
+
+pub const DEFAULT_ATTEMPTS: u32 = 3;
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(200);
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { attempts: DEFAULT_ATTEMPTS, base_delay: DEFAULT_BASE_DELAY, max_delay: DEFAULT_MAX_DELAY }
+    }
+}
+
+impl RetryPolicy {
+    // every request is sent once
+    pub fn none() -> RetryPolicy {
+        RetryPolicy { attempts: 1, ..RetryPolicy::default() }
+    }
+
+    // at least 1
+    pub fn attempts(mut self, attempts: u32) -> RetryPolicy {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    pub fn base_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn max_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.max_delay = delay;
+        self
+    }
+
+    // the backoff after attempt n (1 for the first attempt) , with jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let full = self.base_delay.saturating_mul(1 << attempt.saturating_sub(1).min(30)).min(self.max_delay);
+        let half = full / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=full - half)
+    }
+
+    /*
+        how long to wait before attempt n + 1 , None when there is no attempt n + 1 :
+        the last attempt was made , the method or the outcome is not one to retry ,
+        or the server asked for a wait longer than max_delay
+    */
+    pub fn next_delay(&self, attempt: u32, method: &Method, outcome: &Result<(StatusCode, Option<Duration>), &HttpError>) -> Option<Duration> {
+        if attempt >= self.attempts || !is_idempotent(method) {
+            return None;
+        }
+        let retry_after = match outcome {
+            Ok((status, retry_after)) if is_retryable(*status) => *retry_after,
+            Err(HttpError::Connect { .. }) => None,
+            _ => return None,
+        };
+        let backoff = self.backoff(attempt);
+        match retry_after {
+            Some(wait) if wait > self.max_delay => None,
+            Some(wait) => Some(wait.max(backoff)),
+            None => Some(backoff),
+        }
+    }
+}
+
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE)
+}
+
+pub fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Retry-After : a number of seconds , or an HTTP date ; a date in the past is no wait
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+// one attempt of a request , what came back and what happens next
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub number: u32,
+    pub of: u32,
+    pub method: Method,
+    pub url: String,
+    // the status , or the error when there was no response
+    pub outcome: Result<StatusCode, String>,
+    pub elapsed: Duration,
+    // None when this was the last attempt
+    pub retry_in: Option<Duration>,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} attempt {}/{} -> ", self.method, self.url, self.number, self.of)?;
+        match &self.outcome {
+            Ok(status) => write!(f, "{}", status.as_u16())?,
+            Err(e) => write!(f, "{}", e)?,
+        }
+        write!(f, " ({} ms)", self.elapsed.as_millis())?;
+        if let Some(wait) = self.retry_in {
+            write!(f, " , retrying in {} ms", wait.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default().base_delay(Duration::from_millis(100)).max_delay(Duration::from_millis(1000));
+        for _ in 0..50 {
+            let first = policy.backoff(1);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100), "{:?}", first);
+            let third = policy.backoff(3);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400), "{:?}", third);
+            // capped by max_delay , even after many attempts
+            assert!(policy.backoff(40) <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let policy = RetryPolicy::default().attempts(3).max_delay(Duration::from_secs(5));
+        let status = |code: u16, after: Option<u64>| Ok((StatusCode::from_u16(code).unwrap(), after.map(Duration::from_secs)));
+
+        assert!(policy.next_delay(1, &Method::GET, &status(503, None)).is_some());
+        assert!(policy.next_delay(2, &Method::GET, &status(429, None)).is_some());
+        assert_eq!(policy.next_delay(3, &Method::GET, &status(503, None)), None);
+        assert_eq!(policy.next_delay(1, &Method::POST, &status(503, None)), None);
+        assert_eq!(policy.next_delay(1, &Method::GET, &status(404, None)), None);
+        assert_eq!(policy.next_delay(1, &Method::GET, &status(200, None)), None);
+
+        assert_eq!(policy.next_delay(1, &Method::GET, &status(429, Some(2))), Some(Duration::from_secs(2)));
+        assert_eq!(policy.next_delay(1, &Method::GET, &status(429, Some(60))), None);
+
+        let refused = HttpError::Connect { url: String::new(), message: String::new() };
+        assert!(policy.next_delay(1, &Method::PUT, &Err(&refused)).is_some());
+        let timeout = HttpError::Timeout { url: String::new() };
+        assert_eq!(policy.next_delay(1, &Method::GET, &Err(&timeout)), None);
+        assert_eq!(RetryPolicy::none().next_delay(1, &Method::GET, &status(503, None)), None);
+    }
+
+    // a server that fails on purpose , the first requests get 503 / 429 and the later ones 200
+    #[tokio::test]
+    async fn test_retries_against_a_stub() {
+        use crate::net::stub::{respond, Stub};
+        use crate::net::{HttpClient, Request};
+
+        let stub = Stub::start(|req, n| match (req.uri().path(), n) {
+            ("/flaky", 0) | ("/flaky", 1) => respond(503, "down"),
+            ("/limited", _) if n % 2 == 1 => {
+                let mut r = respond(429, "slow down");
+                r.headers_mut().insert("retry-after", "0".parse().unwrap());
+                r
+            }
+            ("/down", _) => respond(500, "down"),
+            _ => respond(200, "ok"),
+        });
+        let policy = RetryPolicy::default().attempts(3).base_delay(Duration::from_millis(5));
+        let client = HttpClient::builder().base_url(&stub.url("/")).retry(policy).build().unwrap();
+
+        let mut log = Vec::new();
+        let response = client.send_logged(&Request::get("flaky"), |a| log.push(a.clone())).await.unwrap();
+        assert_eq!(response.status, 200);
+        let outcomes: Vec<_> = log.iter().map(|a| (a.number, a.outcome.clone().unwrap().as_u16(), a.retry_in.is_some())).collect();
+        assert_eq!(outcomes, [(1, 503, true), (2, 503, true), (3, 200, false)]);
+        assert!(log[0].to_string().starts_with(&format!("GET {} attempt 1/3 -> 503 (", stub.url("/flaky"))), "{}", log[0]);
+
+        // stub.hits() is 3 now , so the 429 comes first
+        assert_eq!(client.get("limited").await.unwrap().status, 200);
+        assert_eq!(stub.hits(), 5);
+
+        // the attempts run out , the last response is returned
+        let mut log = Vec::new();
+        assert_eq!(client.send_logged(&Request::get("down"), |a| log.push(a.clone())).await.unwrap().status, 500);
+        assert_eq!(log.len(), 3);
+        assert_eq!(client.get("down").await.unwrap_err().exit_code(), 6);
+
+        // a POST is sent once
+        let before = stub.hits();
+        let post = Request::new(Method::POST, "down").body("x");
+        assert_eq!(client.send(&post).await.unwrap().status, 500);
+        assert_eq!(stub.hits(), before + 1);
+
+        // no server at all , every attempt fails to connect
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut log = Vec::new();
+        let e = client.send_logged(&Request::get(&format!("http://127.0.0.1:{}/", port)), |a| log.push(a.clone())).await.unwrap_err();
+        assert_eq!((e.exit_code(), log.len()), (3, 3));
+        assert!(log.iter().all(|a| a.outcome.is_err()));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
@@ -0,0 +1,59 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+
+/*
+    a local HTTP server for the tests of net , so they run without the network.
+    the handler gets every request and the number of requests seen before it (0 for the first).
+    the server runs on the tokio runtime of the test , until the test ends.
+*/
+
+type Handler = dyn Fn(&Request<Body>, usize) -> Response<Body> + Send + Sync;
+
+pub struct Stub {
+    pub addr: SocketAddr,
+    hits: Arc<AtomicUsize>,
+}
+
+impl Stub {
+    pub fn start<F>(handler: F) -> Stub
+    where
+        F: Fn(&Request<Body>, usize) -> Response<Body> + Send + Sync + 'static,
+    {
+        let handler: Arc<Handler> = Arc::new(handler);
+        let hits = Arc::new(AtomicUsize::new(0));
+        let (h, counter) = (handler.clone(), hits.clone());
+        let make_service = make_service_fn(move |_| {
+            let (h, counter) = (h.clone(), counter.clone());
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let n = counter.fetch_add(1, Ordering::SeqCst);
+                    let response = h(&req, n);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        Stub { addr, hits }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    // requests served so far
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::SeqCst)
+    }
+}
+
+// a response with a status and a body
+pub fn respond(status: u16, body: &str) -> Response<Body> {
+    Response::builder().status(status).body(Body::from(body.to_string())).unwrap()
+}
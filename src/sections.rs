@@ -0,0 +1,894 @@
+use std::fs::File; // for reading a file
+use std::io::prelude::*; // for reading a file
+
+use std::collections::HashMap;
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::error::Error;
+
+use rand::Rng;
+use regex::Regex;
+
+use std::io::{self, Write};
+
+use serde_json::Value as JsonValue; // for parsing json
+use serde_json::Result as JsonResult;
+
+use std::io::ErrorKind; // for handling errors below
+
+use crate::dcode;
+use crate::geometry::Rectangle;
+use crate::model::{print_color, Color, Customer, Day, Direction, HasVoiceBox, Person, TupleColor};
+use crate::net;
+use crate::sys::{self, read_data_from_file_v1, read_data_from_file_v2, read_data_from_file_v3};
+use crate::text::{check, get_occupation, print_numbers};
+
+mod my_module {
+    fn print_another_message() {
+        println!("this is another message !");
+    }
+    pub fn print_message() {
+        println!("this is an example of a function inside a module !");
+        print_another_message();
+    }
+
+    pub mod my_test_module {
+        pub fn print_test_message() {
+            println!("this is a test message !");
+        }
+    }
+}
+
+/*
+    Every section of the program is a function with the same signature,
+    so that it can be looked up by name and run on its own :
+
+    rapp1 <section> [args...]
+
+    Each section receives the arguments that follow its name, and returns
+    a Result, so that errors are reported once (in cli::run) with a non-zero exit code.
+*/
+
+pub type SectionResult = Result<(), Box<dyn Error>>;
+
+pub struct Section {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub run: fn(&[String]) -> SectionResult,
+}
+
+pub const SECTIONS: &[Section] = &[
+    Section { name: "basics", about: "loops, enums, tuples, shadowing and references", run: basics },
+    Section { name: "structs", about: "structs, tuple structs, impl blocks and traits", run: structs },
+    Section { name: "arrays", about: "arrays and iterating over them", run: arrays },
+    Section { name: "strings", about: "string methods : split, replace, lines, trim, chars", run: strings },
+    Section { name: "vectors", about: "creating, growing and shrinking vectors", run: vectors },
+    Section { name: "file", about: "read the contents of info.txt", run: file },
+    Section { name: "objects", about: "methods, constructors, Debug and Display traits", run: objects },
+    Section { name: "args", about: "echo the command line arguments", run: args },
+    Section { name: "write", about: "create out.txt and write to it", run: write },
+    Section { name: "match", about: "switch / match, and match and bind", run: match_section },
+    Section { name: "hashmap", about: "insert, get, remove and loop through a hash-map", run: hashmap },
+    Section { name: "random", about: "random numbers and coin flips", run: random },
+    Section { name: "modules", about: "functions inside modules and external modules", run: modules },
+    Section { name: "regex", about: "regular expression matching", run: regex },
+    Section { name: "option", about: "the Option enum", run: option },
+    Section { name: "http", about: "make an http request to jokeapi.dev (needs network)", run: http },
+    Section { name: "enum", about: "methods on enums", run: enum_methods },
+    Section { name: "uptime", about: "execute an external command (uptime)", run: uptime },
+    Section { name: "json", about: "parse a customer from a json string", run: json },
+    Section { name: "result", about: "the Result enum, and handling file errors (creates hello.txt)", run: result },
+    Section { name: "loop", about: "while true loop", run: loop_section },
+    Section { name: "closure", about: "closures", run: closure },
+];
+
+pub fn basics(_args: &[String]) -> SectionResult {
+    println!("Hello, world!");
+
+    let my_strings = ["x", "y", "z"];
+
+    let number_iterator = 1..11;
+
+    for i in number_iterator {
+        println!("value of i is {}", i);
+    }
+
+    for (index,value) in my_strings.iter().enumerate() {
+        println!("index : {} , value : {}", index, value);
+    }
+
+    let player_direction:Direction = Direction::Up;
+
+    match player_direction {
+        Direction::Up => println!("we are heading up !"),
+        Direction::Down => println!("we are heading down !"),
+        Direction::Left => println!("we are heading left !"),
+        Direction::Right => println!("we are heading right !"),
+    }
+
+    let tup1 = (20, 4.44, "Rust", false);
+    let tup2 = (20, 4.44, "Rust", false, ("aa", "bb"));
+
+    println!("{}", tup1.0);
+    println!("{}", tup1.1);
+    println!("{}", tup1.2);
+    println!("{}", tup1.3);
+
+    println!("{}", (tup2.4).1);
+
+    let (v1, v2, v3, v4) = tup1;
+
+    println!("{}", v1);
+    println!("{}", v2);
+    println!("{}", v3);
+    println!("{}", v4);
+
+    print_numbers(10, 21);
+
+    // shadowing
+
+    let mut x = 10;
+    println!("outside scope : value of x is -> {}", x);
+    {
+        let x = 15;
+        println!("inside scope : value of x is -> {}", x);
+    }
+    x = 11;
+    println!("outside scope : value of x is -> {}", x);
+
+    let mut x = "this is a string-01";
+    println!("value of x is -> {}", x);
+
+    x = "this is a string-02";
+    println!("value of x is -> {}", x);
+
+    // references
+    let mut x = 55.55;
+    // immutable reference
+    let xr = &x;
+    println!("xr is => {}", xr);
+
+    // mutable reference (value can be changed through the reference
+    let mut_ref = &mut x;
+    println!("mut_ref is => {}", mut_ref);
+
+    *mut_ref += 100_f64;
+    println!("mut_ref is => {}", mut_ref);
+
+    // x is now changed
+    println!("x is => {}", x);
+
+    Ok(())
+}
+
+pub fn structs(_args: &[String]) -> SectionResult {
+    let background_color = Color{
+        red: 255,
+        green: 70,
+        blue: 15
+    };
+
+    println!("color is -> {} {} {}", background_color.red, background_color.green, background_color.blue);
+
+    // tuple struct
+    let red = TupleColor(255,0,0);
+    println!("red is {}, {}, {}", red.0, red.1, red.2);
+
+    print_color(&background_color);
+
+    let my_rectangle = Rectangle {width: 10, height: 10};
+    println!("{:?}", my_rectangle);
+
+    // impl
+    my_rectangle.print_description();
+
+    println!("Rectangle is square ? {}", my_rectangle.is_square());
+
+    // implementing traits
+
+    let person = Person{
+        name: String::from("Giridhar"),
+        age: 42
+    };
+
+    println!("{}", person);
+
+    // implement 'HasVoiceBox' trait, on 'Person' struct
+
+    let person1 = Person {
+        name: String::from("Giri1"),
+        age: 1,
+    };
+    person1.speak();
+    println!("person1 can speak ? {}", person1.can_speak());
+
+    let person2 = Person {
+        name: String::from("Giri2"),
+        age: 3,
+    };
+    person2.speak();
+    println!("person2 can speak ? {}", person2.can_speak());
+
+    Ok(())
+}
+
+pub fn arrays(_args: &[String]) -> SectionResult {
+    let nums = [0,1,2,3,4,5];
+    println!("arrays : nums[1] => {}", nums[1]);
+
+    for n in nums.iter() {
+        println!("looping through array, value => {}", n)
+    }
+
+    for (i, n) in nums.iter().enumerate() {
+        println!("looping through array, value (i={}) => {}", i, n)
+    }
+
+    // this is what happens internally, that is : nums2:[i32;6] , but not required
+    let nums2:[i32;6] = [0,1,2,3,4,5];
+    for n in nums2.iter() {
+        println!("looping through array, value => {}", n)
+    }
+
+    // array of 20 items, default value of 0
+    let nums3 = [0; 20];
+    for n in nums3.iter() {
+        println!("looping through array, value => {}", n)
+    }
+
+    Ok(())
+}
+
+pub fn strings(_args: &[String]) -> SectionResult {
+    let mut my_string = String::from("the quick brown fox jumps over the lazy dog");
+
+    println!("length of my_string : {}", my_string.len());
+    println!("is my_string empty ? {}", my_string.is_empty());
+
+    let split_words = my_string.split_whitespace();
+
+    for word in split_words {
+        println!("> {}", word);
+    }
+
+    println!("{}", my_string.contains("jumps"));
+    println!("{}", my_string.contains("jump"));
+    println!("{}", my_string.contains("jumps "));
+    println!("{}", my_string.contains("jumps over_"));
+
+    my_string.push_str(" XYZ");
+
+    println!("my_string : {}", my_string);
+
+    // ------------- [ string methods ] -------------
+
+    println!("\n");
+
+    // ------------- replace -------------
+    {
+        let my_str = String::from("rust is fantastic , must check it out please");
+        println!("After Replace : {}\n", my_str.replace("fantastic", "great"));
+    }
+
+    // ------------- lines -------------
+    {
+
+        let my_str = String::from("rust\nis\nfantastic,\nmust\ncheck\nit\nout\nplease");
+        println!("After Replace : \n\n{}\n", my_str.replace("fantastic", "great"));
+
+        for line in my_str.lines() {
+            println!("[ {} ]", line);
+        }
+    }
+
+    println!("\n");
+
+    // ------------- split -------------
+    {
+        let my_str = String::from("rust + is + fantastic, + must + check + it + out + please");
+        let tokens: Vec<&str> = my_str.split('+').collect();
+
+        for token in tokens.iter() {
+            println!("'{}'", token);
+        }
+    }
+
+    println!("\n");
+
+    // ------------- split -------------
+    {
+        let my_str = String::from("rust###is###fantastic,###must###check###it###out###please");
+        let tokens: Vec<&str> = my_str.split("###").collect();
+
+        for token in tokens.iter() {
+            println!("'{}'", token);
+        }
+    }
+
+    println!("\n");
+
+    // ------------- trim -------------
+
+    {
+        let my_str = String::from("   rust   is   fantastic,   must   check   it   out   please   \n\r");
+        println!("Before Trim : \n\n#{}#\n", my_str);
+        println!("After Trim : \n\n#{}#\n", my_str.trim());
+    }
+
+    // ------------- chars -------------
+    {
+        let my_str = String::from("rust is fantastic , must check it out please");
+        println!("{}", my_str);
+        // get char at index
+        match my_str.chars().nth(5) { // character at index 5 (starting from 0)
+            None => {
+                println!("char not found");
+            }
+            Some(c) => {
+                println!("char at index 5 : {}", c);
+            }
+        }
+
+    }
+
+    Ok(())
+}
+
+pub fn vectors(_args: &[String]) -> SectionResult {
+    // one way of creating a vector
+    let mut my_vector1: Vec<i32> = Vec::new();
+
+    // another way of creating a vector
+    let mut my_vector2 = vec![1, 2, 3, 4];
+
+    my_vector1.push(10);
+    my_vector1.push(100);
+    my_vector1.push(1000);
+
+    my_vector2.push(20);
+
+    for (index1, i) in my_vector1.iter().enumerate() {
+        println!("my_vector1 : index : {} , element => {}", index1, i);
+    }
+
+    for (index2, i) in my_vector2.iter().enumerate() {
+        println!("my_vector2 : index : {} , element => {}", index2, i);
+    }
+
+    my_vector1.remove(0);
+    my_vector2.remove(0);
+
+    println!("after removing an element from the vector...");
+
+    for (index1, i) in my_vector1.iter().enumerate() {
+        println!("my_vector1 : index : {} , element => {}", index1, i);
+    }
+
+    for (index2, i) in my_vector2.iter().enumerate() {
+        println!("my_vector2 : index : {} , element => {}", index2, i);
+    }
+
+    Ok(())
+}
+
+pub fn file(_args: &[String]) -> SectionResult {
+    // reading a file
+
+    let mut file = File::open("info.txt")?;
+
+    let mut contents = String::new();
+
+    file.read_to_string(&mut contents)?;
+
+    println!("\nfile contents >>\n");
+
+    println!("{}", contents);
+
+    Ok(())
+}
+
+pub fn objects(_args: &[String]) -> SectionResult {
+    // ------------ using reference ------------
+
+    struct MyObject {
+        width: u32,
+        height: u32
+    }
+
+    fn calculate_area(obj: &MyObject) -> u32 {
+        obj.width * obj.height
+    }
+
+    let my_object = MyObject {
+        width:15,
+        height:20
+    };
+
+    println!("Area of my_object with dimensions {} x {} => {}", my_object.width, my_object.height, calculate_area(&my_object));
+
+    // The above area calculation can be done in a better way >
+
+    impl MyObject {
+        fn calculate_area(&self) -> u32 {
+            self.width * self.height
+        }
+
+        fn show(&self) {
+            println!("Area of the specified object with dimensions {} x {} => {}", self.width, self.height, self.calculate_area());
+        }
+    }
+
+    let my_object_v2 = MyObject {
+        width:35,
+        height:70
+    };
+
+    println!("Area of my_object_v2 with dimensions {} x {} => {}", my_object_v2.width, my_object_v2.height, my_object_v2.calculate_area());
+
+    // creating new objects using 'MyObject::new_object(...)'
+
+    impl MyObject {
+        fn new_object(width: u32, height: u32) -> MyObject {
+            MyObject {
+                width,
+                height
+            }
+        }
+    }
+
+    let my_object_v3 = MyObject::new_object(35,45);
+
+    my_object_v3.show();
+
+    /*
+        FYI:
+        In this print statement >
+        println!("{:?}", my_data);
+        {:?} -> is debug flag/trait
+        {:#?} -> is pretty debug flag/trait
+
+        To apply debug flag/trait, we need to add this:
+        #[derive(Debug)] -> this is an annotation
+    */
+
+    // example of Debug Flag / Debug Trait
+
+    #[derive(Debug)] // this is an annotation
+    struct MyObjectTest {
+        width: u32,
+        height: u32
+    }
+
+    impl MyObjectTest {
+        fn calculate_area(&self) -> u32 {
+            self.width * self.height
+        }
+
+        fn new_object(width: u32, height: u32) -> MyObjectTest {
+            MyObjectTest {
+                width,
+                height
+            }
+        }
+
+        fn show(&self) {
+            println!("Area of the specified object with dimensions {} x {} => {}", self.width, self.height, self.calculate_area());
+        }
+    }
+
+    let my_object_test = MyObjectTest::new_object(99,67);
+    my_object_test.show();
+
+    // using debug flag/trait using {:?}
+    // pretty debug flag/trait {:#?}
+    println!("my_object_test : {:?}", my_object_test);
+    println!("my_object_test : {:#?}", my_object_test);
+
+    // ----------- applying Display trait ----------
+    // import : use std::fmt;
+
+    // the below impl , will enable us to do something like : println!("{}", my_object_test);
+
+    impl fmt::Display for MyObjectTest {
+        /*
+            this peice of code is auto-completed by the IDE
+
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                todo!()
+            }
+
+            We have to replace todo!() -> with out logic
+        */
+
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "(width is {} , height is {}) , Area : {}", self.width, self.height, self.calculate_area())
+        }
+    }
+
+    println!("Here are the dimensions and area for my_object_test : {}", my_object_test);
+
+    Ok(())
+}
+
+pub fn args(args: &[String]) -> SectionResult {
+    println!("\nCLI >>\n");
+
+    for argument in args.iter() {
+        println!("{}", argument);
+    }
+
+    Ok(())
+}
+
+pub fn write(_args: &[String]) -> SectionResult {
+    // creating a file
+
+    let mut file = File::create("out.txt")?;
+
+    file.write_all(b"welcome to rust programming, its awesome !")?;
+
+    println!("wrote out.txt");
+
+    Ok(())
+}
+
+pub fn match_section(_args: &[String]) -> SectionResult {
+    // switch / match
+    let mut my_name = "linus";
+    check(my_name);
+    my_name = "giridhar";
+    check(my_name);
+    my_name = "abc";
+    check(my_name);
+    my_name = "clinton";
+    check(my_name);
+
+    // --------- match and bind -----------
+
+    // use this : ..= , for inclusive range
+    // see below
+
+    let p = 5;
+
+    match p {
+        n @ 1..=10 => println!("n : {}", n), // this line will print > "n : 5"
+        n @ 11..=20 => println!("n : {}", n),
+        _ => println!("oops ! no match found !"),
+    }
+    let p = 15;
+    let n = match p {
+        n @ 1..=10 => n,
+        n @ 11..=20 => n,
+        _ => 0,
+    };
+
+    println!("n : {}", n); // this line will print > "n : 15"
+
+    Ok(())
+}
+
+pub fn hashmap(_args: &[String]) -> SectionResult {
+    // hash-maps
+
+    let mut my_map = HashMap::new();
+
+    my_map.insert("rust programming", 100);
+    my_map.insert("golang programming", 90);
+    my_map.insert("web development", 90);
+    my_map.insert("grpc development", 95);
+
+    println!("total elements in hashmap : {}", my_map.len());
+
+    // get a single value from hashmap
+
+    match my_map.get("web development"){
+        None => {
+            println!("nothing found for key !");
+        }
+        Some(data) => {
+            println!("got this data from hash-map : {}", data);
+        }
+    }
+
+    // remove a value from hash-map
+    my_map.remove("grpc development");
+    println!("total elements in hashmap : {}", my_map.len());
+
+    // loop through hashmap
+    for (key, value) in &my_map {
+        println!("key : {}", key);
+        println!("value : {}", value);
+    }
+    // check for key in map
+    println!("my_map contains key 'xyz' : {}", my_map.contains_key("xyz"));
+    println!("my_map contains key 'rust programming' : {}", my_map.contains_key("rust programming"));
+
+    Ok(())
+}
+
+pub fn random(_args: &[String]) -> SectionResult {
+    // random number
+    let random_number = rand::thread_rng().gen_range(1..11);
+    println!("random_number : {}", random_number);
+
+    // rand : flipping a coin
+    let rand_bool = rand::thread_rng().gen_bool(0.5);
+    println!("rand_bool : {}", rand_bool);
+
+    Ok(())
+}
+
+pub fn modules(_args: &[String]) -> SectionResult {
+    // external modules : 'dcode.rs'
+    // make sure you include : 'pub mod dcode;' in lib.rs , and
+    // function is public : pub fn print_message() { ... } in decode.rs
+    dcode::print_message();
+
+    // -------- module -------
+
+    my_module::print_message();
+    my_module::my_test_module::print_test_message();
+
+    Ok(())
+}
+
+pub fn regex(_args: &[String]) -> SectionResult {
+    {
+        let re = Regex::new(r"\w{5}")?;
+        let text = "the quick brown fox jumps over the lazy dog 5837535 39h3yr3t8 v9d9";
+        println!("Found Match ? {}", re.is_match(text));
+    }
+
+    println!("\n");
+
+    {
+        let re = Regex::new(r"[a-zA-Z]{5}")?;
+        let text = "the quick brown fox jumps over the lazy dog 5837535 39h3yr3t8 v9d9";
+        for cap in re.find_iter(text) {
+            println!("regex : found match : {:#?}", cap.as_str());
+        }
+    }
+
+    println!("\n");
+
+    {
+        let re = Regex::new(r"[0-9]{5}")?;
+        let text = "64646 642436221 744 643466 66002 46642632 33556";
+        for cap in re.find_iter(text) {
+            println!("regex : found match : {:#?}", cap.as_str());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn option(_args: &[String]) -> SectionResult {
+    // option - enum
+
+    let name = String::from("0123456789");
+
+    println!("character at index 10 : {}", match name.chars().nth(9) {
+        None => String::from("no character found at given index !"),
+        Some(c) => c.to_string()
+    });
+
+    println!("1 > occupation is : {}", get_occupation("giridhar").unwrap_or("no occupation found."));
+
+    println!("2 > occupation is : {}", get_occupation("bhujanga").unwrap_or("no occupation found."));
+
+    println!("3 > occupation is : {}", get_occupation("linus").unwrap_or("no occupation found."));
+
+    Ok(())
+}
+
+pub fn http(_args: &[String]) -> SectionResult {
+    // --------- http request ----------
+
+    // the client is built once , every request after that only gives its query
+    // (net::joke wraps an HttpClient with a 10s timeout and the default RetryPolicy)
+    let client = net::joke::JokeClient::new()?;
+    let query = net::joke::JokeQuery::new().safe_mode();
+
+    // an async request , run to the end on the shared runtime ; errors are returned with '?'
+    let joke = net::block_on(client.joke(&query))?;
+
+    // the "type" of the answer picked the variant , so a match is all it takes
+    println!("category : {}", joke.info().category.name());
+    match &joke {
+        net::joke::Joke::Single { joke, .. } => println!("{}", joke),
+        net::joke::Joke::TwoPart { setup, delivery, .. } => println!("{}\n{}", setup, delivery),
+    }
+    Ok(())
+}
+
+pub fn enum_methods(_args: &[String]) -> SectionResult {
+    // -------- enum methods -----------
+
+    let d1 = Day::Tuesday;
+    let d2 = Day::Saturday;
+
+    println!("Tuesday is weekday ? : {}", d1.is_weekday());
+    println!("Saturday is weekday ? : {}", d2.is_weekday());
+
+    Ok(())
+}
+
+pub fn uptime(_args: &[String]) -> SectionResult {
+    // -------- execute external commands ---------
+
+    println!("{}", sys::uptime()?);
+
+    Ok(())
+}
+
+pub fn json(_args: &[String]) -> SectionResult {
+    // -------------- parsing json ----------------
+
+    let json_str = r#"
+        {
+            "customerid": "630c2272eabd3d30fe44d139",
+            "age": 28,
+            "eyecolor": "brown",
+            "name": "Mabel Haley",
+            "gender": "female",
+            "company": "ENOMEN",
+            "email": "mabelhaley@enomen.com",
+            "phone": "+1 (880) 516-2365",
+            "address": "184 Bergen Court, Gorham, American Samoa, 8722"
+        }
+    "#;
+
+    let res: JsonResult<JsonValue> = serde_json::from_str(json_str);
+
+    // method-1 of getting json data
+
+    match res {
+        Ok(deserialized_json) => { // if json_str is a valid json
+            // JsonValue -> is a struct
+            println!("method-1 : customerid : {}", deserialized_json["customerid"].as_str().unwrap_or_default());
+            println!("method-1 : age : {}", deserialized_json["age"].as_i64().unwrap_or_default());
+        }
+        Err(_) => {
+            println!("sorry, could not parse json string !");
+        }
+    }
+
+    // method-2 of getting json data (using structs)
+
+    let c: Customer = serde_json::from_str(json_str)?;
+    println!("method-2 : customerid : {}", c.customerid);
+    println!("method-2 : age : {}", c.age.unwrap_or_default());
+
+    Ok(())
+}
+
+pub fn result(_args: &[String]) -> SectionResult {
+    // --------- Result type -------------
+
+    println!("enter a number: ");
+
+    io::stdout().flush()?;
+
+    let my_num = 51;
+    /*
+        func returns this : Result<u32, &'static str>
+        If it reaches Ok(..) , it will return : u32
+        If it reaches Err(..) , it will return : &'static str (borrowed static string)
+    */
+
+    /*
+    Result -> enum : it represents success or failure
+
+        enum Result<T, E>
+        {
+            Ok(T),
+            Err(E),
+        }
+    */
+
+
+    fn is_it_fifty(num: i32) -> Result<u32, &'static str> {
+        let error = "oops it did not work !";
+
+        if num == 50 {
+            Ok(num as u32)
+        } else {
+            Err(error)
+        }
+    }
+
+    match is_it_fifty(my_num) {
+        Ok(_v) => {
+            println!("Good ! my_num is 50 !");
+        }
+        Err(_e) => {
+            println!("Error ! my_num is not 50 , it is actually : {}", my_num);
+        }
+    }
+
+    let f = File::open("hello.txt");
+
+    // method-1 : simple case
+
+    // let f = match f {
+    //     Ok(file) => file,
+    //     Err(error) => panic!("problem opening the file : {:?}", error),
+    // };
+
+    // method-2 : little more detailed way of handling error
+
+    let _f = match f {
+        Ok(file) => file,
+        Err(error) => match error.kind() {
+            // recover from the error and create the file when it originally was not found.
+            // creating the file could also fail
+            ErrorKind::NotFound => match File::create("hello.txt") {
+                Ok(fc) => fc,
+                Err(e) => panic!("problem creating the file : {:?}", e),
+            },
+            other_error => {
+                panic!("problem opening the file : {:?}", other_error)
+            }
+        },
+    };
+
+    // error handling : the same file, read back using the '?' operator
+
+    println!("hello.txt : {} bytes (v1)", read_data_from_file_v1("hello.txt")?.len());
+    println!("hello.txt : {} bytes (v2)", read_data_from_file_v2("hello.txt")?.len());
+    println!("hello.txt : {} bytes (v3)", read_data_from_file_v3("hello.txt")?.len());
+
+    Ok(())
+}
+
+pub fn loop_section(_args: &[String]) -> SectionResult {
+    // ----- while loop | while true loop --------
+
+    let mut c = 0;
+
+    loop {
+        println!("c => {}", c);
+        c +=1;
+        if c >=10 {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn closure(_args: &[String]) -> SectionResult {
+    // --------- closure ----------
+
+    fn add_one_v1(x: u32) -> u32 {
+        x+1
+    }
+
+    let add_one_v2 = |x: u32| -> u32 {
+        x+1
+    }; // closure ends with a ';'
+
+    let v = add_one_v2(68);
+
+    println!("closure : v : {}", v);
+    println!("function : v : {}", add_one_v1(68));
+
+    // another example  of closure
+
+    let mut rect_list = [
+        Rectangle{width: 100, height: 200},
+        Rectangle{width: 55, height: 65},
+        Rectangle{width: 10, height: 85},
+    ];
+
+    // sort_by_key calls the closure multiple times
+    // therefore, this closure has `FnMut` trait.
+    // however, this does not capture, move or mute
+    // any of the values in the closure body.
+
+    rect_list.sort_by_key(|r| r.width);
+    println!("{:?}", rect_list);
+
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use hyper::header::{HeaderValue, LOCATION};
+use hyper::{Body, Response, StatusCode};
+use serde_json::{json, Value};
+
+use crate::customers::query::{self, Expr, Query};
+use crate::customers::{CustomerStore, StoreError};
+use crate::model::{Customer, ObjectId, RecordField};
+
+use super::{empty, error, json_response, param, State};
+
+// page size when ?page= is given without ?size=
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+// a bigger size is cut down to this one
+pub const MAX_PAGE_SIZE: usize = 1000;
+// a longer ?q= is a 400 , like one nested deeper than query::MAX_DEPTH
+pub const MAX_QUERY_LEN: usize = 2000;
+
+fn parse_id(id: &str) -> Result<ObjectId, Response<Body>> {
+    id.parse().map_err(|e| error(StatusCode::BAD_REQUEST, format!("'{}' is not a customerid : {}", id, e)))
+}
+
+/*
+    the body as a Customer : 400 when it is not a customer at all ,
+    422 when it is one but breaks the validation rules.
+    a body without a customerid gets the one given , if any
+*/
+fn parse_customer(state: &State, body: &[u8], customerid: Option<ObjectId>) -> Result<Customer, Response<Body>> {
+    let mut value: Value = serde_json::from_slice(body).map_err(|e| error(StatusCode::BAD_REQUEST, format!("invalid JSON : {}", e)))?;
+    let record = match value.as_object_mut() {
+        Some(record) => record,
+        None => return Err(error(StatusCode::BAD_REQUEST, "the body must be a JSON object")),
+    };
+    if let Some(id) = customerid {
+        let field = RecordField::CustomerId;
+        if !record.contains_key(field.name()) && !field.aliases().iter().any(|k| record.contains_key(*k)) {
+            record.insert(String::from("customerid"), Value::String(id.to_string()));
+        }
+    }
+
+    let customer: Customer = serde_json::from_value(value).map_err(|e| error(StatusCode::BAD_REQUEST, format!("invalid customer : {}", e)))?;
+    let errors = state.validator.validate(&customer);
+    if !errors.is_empty() {
+        return Err(json_response(StatusCode::UNPROCESSABLE_ENTITY, &json!({ "error": "invalid customer", "fields": errors })));
+    }
+    Ok(customer)
+}
+
+// a store loaded from a file is written back after every change
+fn persist(store: &CustomerStore) -> Result<(), Response<Body>> {
+    if store.path().is_none() {
+        return Ok(());
+    }
+    store.save().map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, format!("could not save the store : {}", e)))
+}
+
+fn store_error(e: StoreError) -> Response<Body> {
+    match e {
+        StoreError::DuplicateId(_) => error(StatusCode::CONFLICT, e.to_string()),
+        StoreError::NotFound(_) => error(StatusCode::NOT_FOUND, e.to_string()),
+        _ => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+// GET /customers?q=<query>&sort=<keys>&page=<n>&size=<n>&offset=<n>&limit=<n>
+pub fn list(state: &State, params: &HashMap<String, String>) -> Response<Body> {
+    let run = || -> Result<Response<Body>, Response<Body>> {
+        let bad_query = |e: query::QueryError| error(StatusCode::BAD_REQUEST, e.to_string());
+        let filter = match params.get("q").filter(|q| !q.trim().is_empty()) {
+            Some(q) if q.chars().count() > MAX_QUERY_LEN => {
+                return Err(error(StatusCode::BAD_REQUEST, format!("the query is longer than {} characters", MAX_QUERY_LEN)));
+            }
+            Some(q) => Some(q.parse::<Expr>().map_err(bad_query)?),
+            None => None,
+        };
+        let mut q = Query {
+            filter,
+            sort: query::parse_sort(params.get("sort").map(String::as_str).unwrap_or("")).map_err(bad_query)?,
+            offset: param(params, "offset")?.unwrap_or(0),
+            limit: param(params, "limit")?,
+        };
+        if let Some(page) = param::<usize>(params, "page")? {
+            if page == 0 {
+                return Err(error(StatusCode::BAD_REQUEST, "pages start at 1"));
+            }
+            let size = param(params, "size")?.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+            q = q.paginate(page, size);
+        }
+
+        let store = state.store();
+        let page = q.run(store.customers());
+        Ok(json_response(StatusCode::OK, &json!({
+            "total": page.total,
+            "filtered": page.filtered,
+            "offset": q.offset,
+            "items": page.items,
+        })))
+    };
+    run().unwrap_or_else(|e| e)
+}
+
+// GET /customers/{id}
+pub fn get(state: &State, id: &str) -> Response<Body> {
+    let id = match parse_id(id) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    match state.store().get(&id) {
+        Some(c) => json_response(StatusCode::OK, c),
+        None => store_error(StoreError::NotFound(id)),
+    }
+}
+
+// POST /customers , 201 with a Location header
+pub fn create(state: &State, body: &[u8]) -> Response<Body> {
+    let run = || -> Result<Response<Body>, Response<Body>> {
+        let customer = parse_customer(state, body, Some(ObjectId::new()))?;
+        let mut store = state.store();
+        store.insert(customer.clone()).map_err(store_error)?;
+        if let Err(response) = persist(&store) {
+            // not saved , so not added
+            store.delete(&customer.customerid).ok();
+            return Err(response);
+        }
+
+        let mut response = json_response(StatusCode::CREATED, &customer);
+        let location = format!("/customers/{}", customer.customerid);
+        response.headers_mut().insert(LOCATION, HeaderValue::from_str(&location).expect("hex digits are a valid header"));
+        Ok(response)
+    };
+    run().unwrap_or_else(|e| e)
+}
+
+// PUT /customers/{id} , the customerid of the body (if any) must be the one of the path
+pub fn replace(state: &State, id: &str, body: &[u8]) -> Response<Body> {
+    let run = || -> Result<Response<Body>, Response<Body>> {
+        let id = parse_id(id)?;
+        let customer = parse_customer(state, body, Some(id))?;
+        if customer.customerid != id {
+            return Err(error(StatusCode::BAD_REQUEST, format!("the body is customer {} , not {}", customer.customerid, id)));
+        }
+        let mut store = state.store();
+        let old = store.update(customer.clone()).map_err(store_error)?;
+        if let Err(response) = persist(&store) {
+            store.update(old).ok();
+            return Err(response);
+        }
+        Ok(json_response(StatusCode::OK, &customer))
+    };
+    run().unwrap_or_else(|e| e)
+}
+
+// DELETE /customers/{id} , 204
+pub fn delete(state: &State, id: &str) -> Response<Body> {
+    let run = || -> Result<Response<Body>, Response<Body>> {
+        let id = parse_id(id)?;
+        let mut store = state.store();
+        let removed = store.delete(&id).map_err(store_error)?;
+        if let Err(response) = persist(&store) {
+            store.insert(removed).ok();
+            return Err(response);
+        }
+        Ok(empty(StatusCode::NO_CONTENT))
+    };
+    run().unwrap_or_else(|e| e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::route;
+    use super::*;
+    use crate::customers::Format;
+    use hyper::Method;
+
+    const MABEL: &str = r#"{
+        "customerid": "630c2272eabd3d30fe44d139",
+        "age": 28,
+        "eyecolor": "brown",
+        "name": "Mabel Haley",
+        "gender": "female",
+        "company": "ENOMEN",
+        "email": "mabelhaley@enomen.com",
+        "phone": "+18805162365",
+        "address": "184 Bergen Court, Gorham, American Samoa, 8722"
+    }"#;
+
+    // the status and the body as JSON (Null for an empty body)
+    fn call(state: &State, method: Method, path: &str, query: &str, body: &str) -> (u16, Value) {
+        let response = route(state, &method, path, query, body.as_bytes());
+        let status = response.status().as_u16();
+        let bytes = body_bytes(response.into_body());
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    fn body_bytes(body: Body) -> Vec<u8> {
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        rt.block_on(hyper::body::to_bytes(body)).unwrap().to_vec()
+    }
+
+    fn state() -> State {
+        let state = State::new(CustomerStore::new());
+        assert_eq!(call(&state, Method::POST, "/customers", "", MABEL).0, 201);
+        state
+    }
+
+    #[test]
+    fn test_crud() {
+        let state = state();
+        let path = "/customers/630c2272eabd3d30fe44d139";
+
+        let (status, body) = call(&state, Method::GET, path, "", "");
+        assert_eq!((status, body["name"].as_str()), (200, Some("Mabel Haley")));
+
+        assert_eq!(call(&state, Method::POST, "/customers", "", MABEL).0, 409);
+
+        let changed = MABEL.replace("\"age\": 28", "\"age\": 29");
+        let (status, body) = call(&state, Method::PUT, path, "", &changed);
+        assert_eq!((status, body["age"].as_u64()), (200, Some(29)));
+
+        assert_eq!(call(&state, Method::DELETE, path, "", "").0, 204);
+        assert_eq!(call(&state, Method::GET, path, "", "").0, 404);
+        assert_eq!(call(&state, Method::DELETE, path, "", "").0, 404);
+        assert_eq!(call(&state, Method::PUT, path, "", MABEL).0, 404);
+    }
+
+    #[test]
+    fn test_bad_requests() {
+        let state = state();
+        assert_eq!(call(&state, Method::GET, "/customers/xyz", "", "").0, 400);
+        assert_eq!(call(&state, Method::POST, "/customers", "", "not json").0, 400);
+        assert_eq!(call(&state, Method::POST, "/customers", "", "[1, 2]").0, 400);
+
+        // a customer , but not a valid one
+        let (status, body) = call(&state, Method::POST, "/customers", "", r#"{"name": "Ann Lee", "age": 300}"#);
+        assert_eq!(status, 422);
+        assert!(body["fields"].as_array().unwrap().iter().any(|f| f["field"] == "age"));
+
+        // the id of the path and of the body differ
+        let other = "/customers/630c2272eabd3d30fe44d140";
+        assert_eq!(call(&state, Method::PUT, other, "", MABEL).0, 400);
+        assert_eq!(call(&state, Method::GET, "/customers", "q=age+%3E", "").0, 400);
+        assert_eq!(call(&state, Method::GET, "/customers", "page=0", "").0, 400);
+        assert_eq!(call(&state, Method::GET, "/customers", "limit=many", "").0, 400);
+    }
+
+    #[test]
+    fn test_post_makes_an_id() {
+        let state = state();
+        let body = MABEL.replace("\"customerid\": \"630c2272eabd3d30fe44d139\",", "");
+        let response = route(&state, &Method::POST, "/customers", "", body.as_bytes());
+        assert_eq!(response.status(), 201);
+        let location = response.headers()[LOCATION].to_str().unwrap().to_string();
+        assert_ne!(location, "/customers/630c2272eabd3d30fe44d139");
+        assert_eq!(call(&state, Method::GET, &location, "", "").0, 200);
+    }
+
+    #[test]
+    fn test_list() {
+        let state = state();
+        let second = MABEL.replace("630c2272eabd3d30fe44d139", "630c2272eabd3d30fe44d140").replace("\"age\": 28", "\"age\": 41");
+        assert_eq!(call(&state, Method::POST, "/customers", "", &second).0, 201);
+
+        let (status, body) = call(&state, Method::GET, "/customers", "q=age+%3E+30", "");
+        assert_eq!(status, 200);
+        assert_eq!((body["total"].as_u64(), body["filtered"].as_u64()), (Some(2), Some(1)));
+
+        let (_, body) = call(&state, Method::GET, "/customers", "sort=-age&page=2&size=1", "");
+        assert_eq!(body["offset"], 1);
+        assert_eq!(body["items"][0]["age"], 28);
+
+        // a size that would overflow the offset
+        let (status, body) = call(&state, Method::GET, "/customers", "page=3&size=18446744073709551615", "");
+        assert_eq!((status, body["offset"].as_u64()), (200, Some(2 * MAX_PAGE_SIZE as u64)));
+        assert_eq!(body["items"].as_array().map(Vec::len), Some(0));
+
+        // a query too long or too deep is refused , it does not take the server down
+        let (status, body) = call(&state, Method::GET, "/customers", &format!("q={}age+%3E+1", "not+".repeat(5000)), "");
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("longer than"), "{}", body);
+        let (status, body) = call(&state, Method::GET, "/customers", &format!("q={}age+%3E+1{}", "(".repeat(100), ")".repeat(100)), "");
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("nest more than"), "{}", body);
+    }
+
+    #[test]
+    fn test_file_backed_store_is_saved() {
+        let path = std::env::temp_dir().join(format!("rapp1-server-{}.json", std::process::id()));
+        CustomerStore::new().save_to(&path, Format::Json).unwrap();
+
+        let state = State::new(CustomerStore::load(&path).unwrap());
+        assert_eq!(call(&state, Method::POST, "/customers", "", MABEL).0, 201);
+        assert_eq!(CustomerStore::load(&path).unwrap().len(), 1);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,192 @@
+// a small table that speaks the DataTables server-side protocol (see src/server/datatables.rs) ,
+// bundled into rapp1 so the page needs nothing but the server : no jQuery , no CDN
+//
+//     rapp1Table(table, { url , columns: [{ data , searchable , orderable }] , order: [[column , 'asc']] , pageLength })
+//
+// every draw POSTs draw , start , length , search[value] , order[0][*] and columns[i][*] as a form ,
+// and shows the data of the answer , the newest draw wins when the answers come back out of order
+function rapp1Table(table, options) {
+    'use strict';
+
+    var columns = options.columns;
+    var state = {
+        draw: 0,
+        start: 0,
+        length: options.pageLength || 10,
+        search: '',
+        order: (options.order || []).map(function (o) { return { column: o[0], dir: o[1] || 'asc' }; })
+    };
+
+    function element(name, attributes, text) {
+        var e = document.createElement(name);
+        Object.keys(attributes || {}).forEach(function (key) { e.setAttribute(key, attributes[key]); });
+        if (text !== undefined) {
+            e.textContent = text;
+        }
+        return e;
+    }
+
+    // the controls around the table : page length and search above , info and pages below
+    var top = element('div', { 'class': 'rapp1-controls' });
+    var lengthSelect = element('select');
+    [10, 25, 50, 100].forEach(function (n) {
+        var option = element('option', { value: String(n) }, String(n));
+        if (n === state.length) {
+            option.selected = true;
+        }
+        lengthSelect.appendChild(option);
+    });
+    var lengthLabel = element('label', {}, 'Show ');
+    lengthLabel.appendChild(lengthSelect);
+    lengthLabel.appendChild(document.createTextNode(' entries'));
+    var searchInput = element('input', { type: 'search' });
+    var searchLabel = element('label', {}, 'Search: ');
+    searchLabel.appendChild(searchInput);
+    top.appendChild(lengthLabel);
+    top.appendChild(searchLabel);
+
+    var bottom = element('div', { 'class': 'rapp1-controls' });
+    var info = element('div', { 'class': 'rapp1-info' });
+    var pages = element('div', { 'class': 'rapp1-pages' });
+    var previous = element('button', { type: 'button' }, 'Previous');
+    var current = element('span');
+    var next = element('button', { type: 'button' }, 'Next');
+    pages.appendChild(previous);
+    pages.appendChild(current);
+    pages.appendChild(next);
+    bottom.appendChild(info);
+    bottom.appendChild(pages);
+
+    table.parentNode.insertBefore(top, table);
+    table.parentNode.insertBefore(bottom, table.nextSibling);
+
+    var body = table.tBodies[0] || table.appendChild(element('tbody'));
+    var headers = table.tHead ? table.tHead.rows[0].cells : [];
+
+    function form() {
+        var values = [
+            ['draw', state.draw],
+            ['start', state.start],
+            ['length', state.length],
+            ['search[value]', state.search]
+        ];
+        state.order.forEach(function (o, i) {
+            values.push(['order[' + i + '][column]', o.column]);
+            values.push(['order[' + i + '][dir]', o.dir]);
+        });
+        columns.forEach(function (c, i) {
+            values.push(['columns[' + i + '][data]', c.data || '']);
+            values.push(['columns[' + i + '][searchable]', c.searchable === false ? 'false' : 'true']);
+            values.push(['columns[' + i + '][orderable]', c.orderable === false ? 'false' : 'true']);
+        });
+        return values.map(function (v) { return encodeURIComponent(v[0]) + '=' + encodeURIComponent(v[1]); }).join('&');
+    }
+
+    function showHeaders() {
+        Array.prototype.forEach.call(headers, function (th, i) {
+            var o = state.order.filter(function (o) { return o.column === i; })[0];
+            th.className = columns[i] && columns[i].orderable === false ? '' : (o ? 'sorting-' + o.dir : 'sorting');
+        });
+    }
+
+    function showRows(answer) {
+        body.textContent = '';
+        if (answer.data.length === 0) {
+            var empty = element('td', { colspan: String(columns.length), 'class': 'rapp1-empty' }, 'No matching records found');
+            body.appendChild(element('tr')).appendChild(empty);
+        }
+        answer.data.forEach(function (row) {
+            var tr = body.appendChild(element('tr'));
+            columns.forEach(function (c) {
+                var value = c.data ? row[c.data] : '';
+                // textContent , the values are never read as HTML
+                tr.appendChild(element('td', {}, value === undefined || value === null ? '' : String(value)));
+            });
+        });
+
+        var filtered = answer.recordsFiltered;
+        var last = Math.min(state.start + state.length, filtered);
+        var text = filtered === 0 ? 'Showing 0 entries' : 'Showing ' + (state.start + 1) + ' to ' + last + ' of ' + filtered + ' entries';
+        if (filtered !== answer.recordsTotal) {
+            text += ' (filtered from ' + answer.recordsTotal + ' total entries)';
+        }
+        info.textContent = text;
+        current.textContent = ' page ' + (filtered === 0 ? 0 : Math.floor(state.start / state.length) + 1) + ' of ' + Math.ceil(filtered / state.length) + ' ';
+        previous.disabled = state.start === 0;
+        next.disabled = last >= filtered;
+    }
+
+    function draw() {
+        state.draw += 1;
+        var sent = state.draw;
+        showHeaders();
+        table.classList.add('rapp1-processing');
+        var request = new XMLHttpRequest();
+        request.open('POST', options.url);
+        request.setRequestHeader('Content-Type', 'application/x-www-form-urlencoded');
+        request.onload = function () {
+            var answer = null;
+            try {
+                answer = JSON.parse(request.responseText);
+            } catch (e) {
+                answer = { error: request.statusText };
+            }
+            // an older draw , a newer one is on its way
+            if (answer.draw !== undefined && answer.draw !== sent) {
+                return;
+            }
+            table.classList.remove('rapp1-processing');
+            if (request.status !== 200) {
+                info.textContent = 'Error : ' + (answer.error || request.status);
+                return;
+            }
+            showRows(answer);
+        };
+        request.onerror = function () {
+            table.classList.remove('rapp1-processing');
+            info.textContent = 'Error : the server did not answer';
+        };
+        request.send(form());
+    }
+
+    lengthSelect.addEventListener('change', function () {
+        state.length = Number(lengthSelect.value);
+        state.start = 0;
+        draw();
+    });
+
+    var searchTimer = null;
+    searchInput.addEventListener('input', function () {
+        clearTimeout(searchTimer);
+        searchTimer = setTimeout(function () {
+            state.search = searchInput.value;
+            state.start = 0;
+            draw();
+        }, options.searchDelay || 300);
+    });
+
+    previous.addEventListener('click', function () {
+        state.start = Math.max(0, state.start - state.length);
+        draw();
+    });
+    next.addEventListener('click', function () {
+        state.start += state.length;
+        draw();
+    });
+
+    // a click on a header sorts by that column , a second click reverses it
+    Array.prototype.forEach.call(headers, function (th, i) {
+        if (!columns[i] || columns[i].orderable === false) {
+            return;
+        }
+        th.addEventListener('click', function () {
+            var o = state.order[0];
+            var dir = o && o.column === i && o.dir === 'asc' ? 'desc' : 'asc';
+            state.order = [{ column: i, dir: dir }];
+            state.start = 0;
+            draw();
+        });
+    });
+
+    draw();
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Response, StatusCode};
+use serde_json::{json, Value};
+
+use crate::customers::query::{self, Expr, Op, Query, SortKey};
+
+use super::{error, json_response, param, State};
+
+/*
+    server-side processing for jQuery DataTables (see Rust-Data-Table.md) ,
+    the table sends on every draw :
+
+        draw                  -> a counter , sent back as it came so old answers can be dropped
+        start , length        -> the page , a length of -1 means every row
+        search[value]         -> text to look for , every word must be in one of the searchable columns
+        order[i][column]      -> index into columns[] , order[i][dir] is asc or desc
+        columns[i][data]      -> the field shown in column i (a Customer field , city , region or postal_code) ,
+                                 empty for a column with data: null (buttons , a counter ...) ,
+                                 which is kept for the indexes of order[] but never searched or sorted
+        columns[i][searchable] , columns[i][orderable] -> "false" to leave a column out
+
+    and gets back { draw , recordsTotal , recordsFiltered , data } , which is a
+    query::Query run : recordsTotal -> total , recordsFiltered -> filtered , data -> items
+*/
+
+// the page size when the table does not send one , the DataTables default
+pub const DEFAULT_LENGTH: usize = 10;
+
+/*
+    the bundled page , a table of all the Customer fields that calls /datatables.
+    its script and style are bundled too and served from /assets/ , nothing comes from the network :
+    datatables.js is a small client of the protocol above , in place of jQuery DataTables
+*/
+pub const PAGE: &str = include_str!("datatables.html");
+pub const SCRIPT: &str = include_str!("datatables.js");
+pub const STYLE: &str = include_str!("datatables.css");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub data: String,
+    pub searchable: bool,
+    pub orderable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub draw: u64,
+    pub start: usize,
+    // None for every row
+    pub length: Option<usize>,
+    pub search: String,
+    // (column index , descending)
+    pub order: Vec<(usize, bool)>,
+    pub columns: Vec<Column>,
+}
+
+// a flag of the form , anything but "false" is true
+fn flag(params: &HashMap<String, String>, name: &str) -> bool {
+    params.get(name).is_none_or(|v| v != "false")
+}
+
+impl Request {
+    // the columns and the order are read until the first index that is missing
+    pub fn parse(params: &HashMap<String, String>) -> Result<Request, Response<Body>> {
+        let length = match param::<i64>(params, "length")? {
+            None => Some(DEFAULT_LENGTH),
+            Some(n) if n < 0 => None,
+            Some(n) => Some(n as usize),
+        };
+
+        let mut columns = Vec::new();
+        while let Some(data) = params.get(&format!("columns[{}][data]", columns.len())) {
+            let i = columns.len();
+            if data.is_empty() {
+                columns.push(Column { data: String::new(), searchable: false, orderable: false });
+                continue;
+            }
+            if !query::is_field(data) {
+                return Err(error(StatusCode::BAD_REQUEST, format!("columns[{}][data] : unknown field '{}'", i, data)));
+            }
+            columns.push(Column {
+                data: data.clone(),
+                searchable: flag(params, &format!("columns[{}][searchable]", i)),
+                orderable: flag(params, &format!("columns[{}][orderable]", i)),
+            });
+        }
+
+        let mut order = Vec::new();
+        while let Some(column) = param::<usize>(params, &format!("order[{}][column]", order.len()))? {
+            let i = order.len();
+            if column >= columns.len() {
+                return Err(error(StatusCode::BAD_REQUEST, format!("order[{}][column] : there is no column {}", i, column)));
+            }
+            let descending = match params.get(&format!("order[{}][dir]", i)).map(String::as_str) {
+                None | Some("asc") => false,
+                Some("desc") => true,
+                Some(dir) => return Err(error(StatusCode::BAD_REQUEST, format!("order[{}][dir] : '{}' is not asc or desc", i, dir))),
+            };
+            order.push((column, descending));
+        }
+
+        Ok(Request {
+            draw: param(params, "draw")?.unwrap_or(0),
+            start: param(params, "start")?.unwrap_or(0),
+            length,
+            search: params.get("search[value]").cloned().unwrap_or_default(),
+            order,
+            columns,
+        })
+    }
+
+    /*
+        the same Query the list endpoint runs : every word of the search must be
+        contained in one of the searchable columns , "mabel enomen" finds Mabel at ENOMEN
+    */
+    pub fn query(&self) -> Query {
+        let searchable: Vec<&str> = self.columns.iter().filter(|c| c.searchable).map(|c| c.data.as_str()).collect();
+        let word = |w: &str| {
+            searchable
+                .iter()
+                .map(|field| Expr::Compare { field: field.to_string(), op: Op::Contains, value: w.to_string() })
+                .reduce(|a, b| Expr::Or(Box::new(a), Box::new(b)))
+        };
+        let filter = self
+            .search
+            .split_whitespace()
+            .filter_map(word)
+            .reduce(|a, b| Expr::And(Box::new(a), Box::new(b)));
+
+        let sort = self
+            .order
+            .iter()
+            .map(|&(i, descending)| (&self.columns[i], descending))
+            .filter(|(c, _)| c.orderable)
+            .map(|(c, descending)| SortKey { field: c.data.clone(), descending })
+            .collect();
+
+        Query { filter, sort, offset: self.start, limit: self.length }
+    }
+}
+
+// GET or POST /datatables
+pub fn datatables(state: &State, params: &HashMap<String, String>) -> Response<Body> {
+    let request = match Request::parse(params) {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+    let q = request.query();
+    let store = state.store();
+    let page = q.run(store.customers());
+
+    // the address fields are not keys of a customer , they are added when a column shows them
+    let data: Vec<Value> = page
+        .items
+        .iter()
+        .map(|c| {
+            let mut row = serde_json::to_value(c).unwrap_or_default();
+            if let Some(row) = row.as_object_mut() {
+                for column in request.columns.iter().filter(|c| !c.data.is_empty()) {
+                    if !row.contains_key(&column.data) {
+                        row.insert(column.data.clone(), Value::String(query::field_value(c, &column.data)));
+                    }
+                }
+            }
+            row
+        })
+        .collect();
+
+    json_response(StatusCode::OK, &json!({
+        "draw": request.draw,
+        "recordsTotal": page.total,
+        "recordsFiltered": page.filtered,
+        "data": data,
+    }))
+}
+
+fn bundled(body: &'static str, content_type: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+// GET /
+pub fn page() -> Response<Body> {
+    bundled(PAGE, "text/html; charset=utf-8")
+}
+
+// GET /assets/{name} , the script and style of the page , None for any other name
+pub fn asset(name: &str) -> Option<Response<Body>> {
+    match name {
+        "datatables.js" => Some(bundled(SCRIPT, "text/javascript; charset=utf-8")),
+        "datatables.css" => Some(bundled(STYLE, "text/css; charset=utf-8")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{params, route};
+    use super::*;
+    use crate::customers::CustomerStore;
+    use crate::model::Customer;
+    use hyper::Method;
+
+    fn customer(id: &str, name: &str, age: u32, company: &str) -> Customer {
+        Customer {
+            company: company.to_string(),
+            email: format!("{}@{}.com", name.split(' ').next().unwrap().to_lowercase(), company.to_lowercase()),
+            ..Customer::sample(id, name, age)
+        }
+    }
+
+    fn state() -> State {
+        let mut store = CustomerStore::new();
+        store.insert(customer("a", "Mabel Haley", 28, "ENOMEN")).unwrap();
+        store.insert(customer("b", "Whitney Carver", 41, "ZILLACON")).unwrap();
+        store.insert(customer("c", "Ann Lee", 35, "ENOMEN")).unwrap();
+        State::new(store)
+    }
+
+    const COLUMNS: &str = "columns[0][data]=name&columns[1][data]=age&columns[1][searchable]=false&columns[2][data]=company&columns[3][data]=city";
+
+    fn call(state: &State, method: Method, query: &str, body: &str) -> (u16, Value) {
+        let response = route(state, &method, "/datatables", query, body.as_bytes());
+        let status = response.status().as_u16();
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let bytes = rt.block_on(hyper::body::to_bytes(response.into_body())).unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    fn names(body: &Value) -> Vec<&str> {
+        body["data"].as_array().unwrap().iter().map(|row| row["name"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let r = Request::parse(&params(&format!("draw=3&start=10&length=-1&search%5Bvalue%5D=en&order[0][column]=1&order[0][dir]=desc&{}", COLUMNS))).unwrap();
+        assert_eq!((r.draw, r.start, r.length, r.search.as_str()), (3, 10, None, "en"));
+        assert_eq!(r.order, vec![(1, true)]);
+        assert_eq!(r.columns.len(), 4);
+        assert!(!r.columns[1].searchable && r.columns[1].orderable);
+
+        // a column without data (data: null in the table) is there , but never searched or sorted
+        let r = Request::parse(&params("columns[0][data]=&columns[1][data]=name&order[0][column]=0&order[1][column]=1&search[value]=ma")).unwrap();
+        assert_eq!(r.columns[0], Column { data: String::new(), searchable: false, orderable: false });
+        let q = r.query();
+        assert_eq!(q.sort, vec![SortKey { field: String::from("name"), descending: false }]);
+        assert_eq!(q.filter, Some(Expr::Compare { field: String::from("name"), op: Op::Contains, value: String::from("ma") }));
+
+        let r = Request::parse(&params("")).unwrap();
+        assert_eq!((r.draw, r.start, r.length), (0, 0, Some(DEFAULT_LENGTH)));
+
+        for bad in ["columns[0][data]=nope", "columns[0][data]=age&order[0][column]=1", "columns[0][data]=age&order[0][column]=0&order[0][dir]=up", "start=x"] {
+            assert!(Request::parse(&params(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_draw() {
+        let state = state();
+        let (status, body) = call(&state, Method::GET, &format!("draw=7&start=0&length=2&order[0][column]=1&order[0][dir]=desc&{}", COLUMNS), "");
+        assert_eq!(status, 200);
+        assert_eq!((body["draw"].as_u64(), body["recordsTotal"].as_u64(), body["recordsFiltered"].as_u64()), (Some(7), Some(3), Some(3)));
+        assert_eq!(names(&body), ["Whitney Carver", "Ann Lee"]);
+        // city is not a key of a customer , it is added for the column
+        assert_eq!(body["data"][0]["city"], "Gorham");
+
+        // every word must match a searchable column , age is not searchable
+        let (_, body) = call(&state, Method::GET, &format!("search[value]=enomen+ma&{}", COLUMNS), "");
+        assert_eq!((body["recordsFiltered"].as_u64(), names(&body)), (Some(1), vec!["Mabel Haley"]));
+        let (_, body) = call(&state, Method::GET, &format!("search[value]=41&{}", COLUMNS), "");
+        assert_eq!(body["recordsFiltered"], 0);
+
+        // the form body of a POST
+        let (status, body) = call(&state, Method::POST, "", &format!("draw=2&start=1&length=1&order[0][column]=0&{}", COLUMNS));
+        assert_eq!((status, body["draw"].as_u64(), names(&body)), (200, Some(2), vec!["Mabel Haley"]));
+
+        assert_eq!(call(&state, Method::GET, "columns[0][data]=nope", "").0, 400);
+        let (status, body) = call(&state, Method::GET, "columns[0][data]=&columns[1][data]=name&order[0][column]=0", "");
+        assert_eq!((status, body["recordsFiltered"].as_u64()), (200, Some(3)));
+        assert!(body["data"][0].get("").is_none());
+    }
+
+    #[test]
+    fn test_page() {
+        let response = route(&state(), &Method::GET, "/", "", b"");
+        assert_eq!(response.status(), 200);
+        assert!(response.headers()[CONTENT_TYPE].to_str().unwrap().starts_with("text/html"));
+        assert!(PAGE.contains("/datatables"));
+
+        // everything the page loads is served by rapp1 itself
+        assert!(!PAGE.contains("https://") && !PAGE.contains("http://"));
+        for (path, content_type) in [("/assets/datatables.js", "text/javascript"), ("/assets/datatables.css", "text/css")] {
+            assert!(PAGE.contains(path), "{}", path);
+            let response = route(&state(), &Method::GET, path, "", b"");
+            assert_eq!(response.status(), 200, "{}", path);
+            assert!(response.headers()[CONTENT_TYPE].to_str().unwrap().starts_with(content_type));
+        }
+        assert!(SCRIPT.contains("function rapp1Table") && !SCRIPT.contains("https://"));
+        assert_eq!(route(&state(), &Method::GET, "/assets/jquery.js", "", b"").status(), 404);
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::customers::{CustomerStore, Validator};
+
+mod customers;
+mod datatables;
+
+/*
+    server -> a small HTTP server over a CustomerStore , on the tokio runtime
+
+    GET    /customers          list , ?q=<query>&sort=<keys>&page=<n>&size=<n>&offset=<n>&limit=<n>
+                               (q at most MAX_QUERY_LEN characters , size at most MAX_PAGE_SIZE)
+    POST   /customers          add a customer , 201 ; the customerid is made when it is missing
+    GET    /customers/{id}     one customer
+    PUT    /customers/{id}     replace a customer
+    DELETE /customers/{id}     remove a customer , 204
+
+    GET    /                   a page with a DataTables table of the customers
+    GET    /assets/{name}      its script and style , bundled like the page
+    GET    /datatables         the DataTables server-side protocol , see datatables.rs ;
+    POST   /datatables         the same , with the parameters in a form body
+
+    bodies are JSON customers (see model::schema) and are checked by customers::Validator.
+    a store loaded from a file is saved after every change , a new store only lives in memory.
+
+    route() does the work without any network , so the endpoints can be tested by calling it ;
+    serve() runs it behind hyper , on the blocking threads of tokio (spawn_blocking) :
+    route() waits for the store lock and rewrites the whole file on every change ,
+    which must not hold up a runtime worker and every connection on it.
+*/
+
+// requests with a bigger body are refused with 413
+pub const MAX_BODY: usize = 1024 * 1024;
+
+pub const DEFAULT_PORT: u16 = 8080;
+
+pub struct State {
+    store: Mutex<CustomerStore>,
+    validator: Validator,
+}
+
+impl State {
+    pub fn new(store: CustomerStore) -> State {
+        State { store: Mutex::new(store), validator: Validator::new() }
+    }
+
+    // a handler that panicked leaves the store as it was , so a poisoned lock is still usable
+    pub fn store(&self) -> MutexGuard<'_, CustomerStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ------------- responses -------------
+
+pub fn json_response<T: Serialize + ?Sized>(status: StatusCode, value: &T) -> Response<Body> {
+    let body = serde_json::to_vec(value).unwrap_or_default();
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+// {"error": "..."}
+pub fn error(status: StatusCode, message: impl Into<String>) -> Response<Body> {
+    json_response(status, &json!({ "error": message.into() }))
+}
+
+pub fn empty(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+fn method_not_allowed(allowed: &'static str) -> Response<Body> {
+    let mut response = error(StatusCode::METHOD_NOT_ALLOWED, "method not allowed");
+    response.headers_mut().insert(hyper::header::ALLOW, HeaderValue::from_static(allowed));
+    response
+}
+
+// ------------- requests -------------
+
+// the query string as a map , a name given twice keeps its last value
+pub fn params(query: &str) -> HashMap<String, String> {
+    form_urlencoded::parse(query.as_bytes()).into_owned().collect()
+}
+
+// a parameter parsed , a value that does not parse is a 400
+pub fn param<T: std::str::FromStr>(params: &HashMap<String, String>, name: &str) -> Result<Option<T>, Response<Body>> {
+    match params.get(name) {
+        None => Ok(None),
+        Some(v) => v
+            .parse()
+            .map(Some)
+            .map_err(|_| error(StatusCode::BAD_REQUEST, format!("invalid value '{}' for {}", v, name))),
+    }
+}
+
+pub fn route(state: &State, method: &Method, path: &str, query: &str, body: &[u8]) -> Response<Body> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments[..] {
+        [""] => match *method {
+            Method::GET => datatables::page(),
+            _ => method_not_allowed("GET"),
+        },
+        ["assets", name] => match (method, datatables::asset(name)) {
+            (&Method::GET, Some(response)) => response,
+            (_, Some(_)) => method_not_allowed("GET"),
+            (_, None) => error(StatusCode::NOT_FOUND, format!("no asset {}", name)),
+        },
+        ["datatables"] => match *method {
+            Method::GET => datatables::datatables(state, &params(query)),
+            Method::POST => datatables::datatables(state, &params(&String::from_utf8_lossy(body))),
+            _ => method_not_allowed("GET, POST"),
+        },
+        ["customers"] => match *method {
+            Method::GET => customers::list(state, &params(query)),
+            Method::POST => customers::create(state, body),
+            _ => method_not_allowed("GET, POST"),
+        },
+        ["customers", id] => match *method {
+            Method::GET => customers::get(state, id),
+            Method::PUT => customers::replace(state, id, body),
+            Method::DELETE => customers::delete(state, id),
+            _ => method_not_allowed("GET, PUT, DELETE"),
+        },
+        _ => error(StatusCode::NOT_FOUND, format!("no route for {}", path)),
+    }
+}
+
+// read the body (up to MAX_BODY) , then route
+async fn handle(state: Arc<State>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let (parts, mut body) = req.into_parts();
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => return Ok(error(StatusCode::BAD_REQUEST, e.to_string())),
+        };
+        if bytes.len() + chunk.len() > MAX_BODY {
+            return Ok(error(StatusCode::PAYLOAD_TOO_LARGE, format!("the body is bigger than {} bytes", MAX_BODY)));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let (method, uri) = (parts.method.clone(), parts.uri.clone());
+    let response = tokio::task::spawn_blocking(move || route(&state, &method, uri.path(), uri.query().unwrap_or(""), &bytes))
+        .await
+        // route() panicked , the store is still usable (see State::store)
+        .unwrap_or_else(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+    println!("{} {} -> {}", parts.method, parts.uri, response.status().as_u16());
+    Ok(response)
+}
+
+// only on the loopback interface , the server is not meant to be reachable from other machines
+pub fn bind(port: u16) -> std::io::Result<TcpListener> {
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port)))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+// serve until shutdown completes , must be called from inside a tokio runtime
+pub async fn serve<F>(listener: TcpListener, state: Arc<State>, shutdown: F) -> hyper::Result<()>
+where
+    F: Future<Output = ()>,
+{
+    let make_service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+    });
+    Server::from_tcp(listener)?.serve(make_service).with_graceful_shutdown(shutdown).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        let state = State::new(CustomerStore::new());
+        assert_eq!(route(&state, &Method::GET, "/nope", "", b"").status(), StatusCode::NOT_FOUND);
+        let r = route(&state, &Method::PATCH, "/customers", "", b"");
+        assert_eq!(r.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(r.headers()[hyper::header::ALLOW], "GET, POST");
+        assert_eq!(params("q=age+%3E+30&size=5&size=10")["q"], "age > 30");
+        assert_eq!(params("size=5&size=10")["size"], "10");
+    }
+
+    // a real server on a free port of 127.0.0.1 , called with reqwest
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = bind(0).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, Arc::new(State::new(CustomerStore::new())), async {
+            stopped.await.ok();
+        }));
+
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let url = format!("http://{}/customers", addr);
+        let mabel = serde_json::to_string(&crate::customers::generate::Generator::new(1).customer()).unwrap();
+        let created = client.post(&url).body(mabel).send().await.unwrap();
+        assert_eq!(created.status(), 201);
+        let location = created.headers()["location"].to_str().unwrap().to_string();
+
+        let list: serde_json::Value = serde_json::from_str(&client.get(&url).send().await.unwrap().text().await.unwrap()).unwrap();
+        assert_eq!(list["total"], 1);
+
+        let got = client.get(format!("http://{}{}", addr, location)).send().await.unwrap();
+        assert_eq!(got.status(), 200);
+
+        let big = vec![b' '; MAX_BODY + 1];
+        assert_eq!(client.post(&url).body(big).send().await.unwrap().status(), 413);
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    // a request waiting for the store does not hold up the one runtime thread , the page is still served
+    #[tokio::test]
+    async fn test_store_work_does_not_block_the_runtime() {
+        let listener = bind(0).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(State::new(CustomerStore::new()));
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, state.clone(), async {
+            stopped.await.ok();
+        }));
+
+        // the lock is held until the test is done , or 3 seconds at most so a blocked runtime can not hang the test
+        let (locked, unlock) = (std::sync::mpsc::channel(), std::sync::mpsc::channel::<()>());
+        let holder = std::thread::spawn(move || {
+            let _store = state.store();
+            locked.0.send(()).unwrap();
+            unlock.1.recv_timeout(std::time::Duration::from_secs(3)).ok();
+        });
+        locked.1.recv().unwrap();
+
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let waiting = tokio::spawn(client.get(format!("http://{}/customers", addr)).send());
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let start = std::time::Instant::now();
+        let page = client.get(format!("http://{}/", addr)).send().await.unwrap();
+        assert_eq!(page.status(), 200);
+        assert!(start.elapsed() < std::time::Duration::from_secs(1), "the page waited {:?} for the store", start.elapsed());
+
+        unlock.0.send(()).unwrap();
+        holder.join().unwrap();
+        assert_eq!(waiting.await.unwrap().unwrap().status(), 200);
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,70 @@
+use std::fs::File; // for reading a file
+use std::io;
+use std::io::prelude::*; // for reading a file
+use std::path::Path;
+use std::process::Command; // for executing external commands
+
+// Error handling example-1
+
+#[allow(clippy::question_mark)] // written out on purpose, see example-2 for the short form
+pub fn read_data_from_file_v1<P: AsRef<Path>>(path: P) -> Result<String, io::Error> {
+    let f = File::open(path);
+
+    let mut f = match f {
+        Ok(file) => file,
+        Err(e) => return Err(e),
+    };
+
+    let mut s = String::new();
+
+    match f.read_to_string(&mut s) {
+        Ok(_) => Ok(s),
+        Err(e) => Err(e),
+    }
+}
+
+// error handling example-2
+
+/*
+In the below example, we have this >
+let mut f = File::open(path)?;
+and this >
+f.read_to_string(&mut s)?;
+
+? symbol : means, if there is an error, then return it immediately (rust will take care of it)
+*/
+pub fn read_data_from_file_v2<P: AsRef<Path>>(path: P) -> Result<String, io::Error> {
+    let mut f = File::open(path)?;
+    let mut s = String::new();
+    f.read_to_string(&mut s)?;
+    Ok(s)
+}
+
+pub fn read_data_from_file_v3<P: AsRef<Path>>(path: P) -> Result<String, io::Error> {
+    // example of chaining , that is reducing the lines of code in read_data_from_file_v2()
+    let mut s = String::new();
+    File::open(path)?.read_to_string(&mut s)?;
+    Ok(s)
+}
+
+// execute an external command , and return what it printed
+pub fn uptime() -> Result<String, io::Error> {
+    let mut cmd = Command::new("uptime");
+    // cmd.arg("-h");
+
+    let o = cmd.output()?;
+    Ok(String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_data_from_file() {
+        let v1 = read_data_from_file_v1("info.txt").unwrap();
+        assert_eq!(v1, read_data_from_file_v2("info.txt").unwrap());
+        assert_eq!(v1, read_data_from_file_v3("info.txt").unwrap());
+        assert!(read_data_from_file_v2("no-such-file.txt").is_err());
+    }
+}
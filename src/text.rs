@@ -0,0 +1,53 @@
+pub fn get_occupation(name: &str) -> Option<&str> {
+    match name {
+        "giridhar" => Some("software developer"),
+        "linus" => Some("linux kernel developer"),
+        _ => None
+    }
+}
+
+pub fn check(my_name: &str) {
+    match my_name {
+        "linus" => {
+            println!("he created linux !");
+        }
+        "giridhar" => {
+            println!("he is getting better at RUST language");
+        }
+        "clinton" | "bush" => {
+            println!("they were US presidents !");
+        }
+        _ => {
+            println!("oops : does not match anything");
+        }
+    }
+}
+
+pub fn print_numbers(start: u32, end: u32) {
+    for i in start..end {
+        let (n, is_even) = is_even(i);
+        println!("{} => {}", n, is_even);
+    }
+}
+
+pub fn is_even(num: u32) -> (u32, bool) {
+    let num_even = num.is_multiple_of(2);
+    (num, num_even)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_occupation() {
+        assert_eq!(get_occupation("linus"), Some("linux kernel developer"));
+        assert_eq!(get_occupation("bhujanga"), None);
+    }
+
+    #[test]
+    fn test_is_even() {
+        assert_eq!(is_even(10), (10, true));
+        assert_eq!(is_even(11), (11, false));
+    }
+}